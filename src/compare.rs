@@ -0,0 +1,351 @@
+//! Value Comparison
+//!
+//! This module defines the ways `MoltValue`s can be compared, as used by `lsort`:
+//! as ASCII strings, as strings ignoring case, in dictionary order, as integers,
+//! and as real numbers.  See [`CompareMode`].
+//!
+//! The numeric comparisons use the values' data reps, converting the values if
+//! need be, and fail if either value isn't numeric.  The string comparisons never
+//! fail.
+//!
+//! [`CompareMode`]: enum.CompareMode.html
+
+use crate::value::MoltFloat;
+use crate::value::MoltValue;
+use crate::value::RepKind;
+use crate::value::ResultCode;
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+/// How to compare two `MoltValue`s.  See `MoltValue::compare`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompareMode {
+    /// Compare the string reps by Unicode code point.  This is the default.
+    #[default]
+    Ascii,
+
+    /// Compare the string reps by Unicode code point, ignoring case.
+    Nocase,
+
+    /// Compare the string reps in dictionary order: ignoring case except as a
+    /// tie-breaker, and comparing embedded numbers numerically.  See `dictionary_cmp`.
+    Dictionary,
+
+    /// Compare the values as integers.
+    Integer,
+
+    /// Compare the values as real numbers.
+    Real,
+}
+
+// CompareModes are displayed by the name of the matching `lsort` option, e.g.,
+// "ascii".
+impl Display for CompareMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CompareMode::Ascii => write!(f, "ascii"),
+            CompareMode::Nocase => write!(f, "nocase"),
+            CompareMode::Dictionary => write!(f, "dictionary"),
+            CompareMode::Integer => write!(f, "integer"),
+            CompareMode::Real => write!(f, "real"),
+        }
+    }
+}
+
+impl MoltValue {
+    /// Compares the value with another value using the given mode.  Integer and
+    /// real comparisons return an error if either value isn't numeric.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use value::value::MoltValue;
+    /// use value::compare::CompareMode;
+    /// use std::cmp::Ordering;
+    ///
+    /// let a = MoltValue::from_string("x9".to_string());
+    /// let b = MoltValue::from_string("x10".to_string());
+    /// assert_eq!(a.compare(&b, CompareMode::Ascii), Ok(Ordering::Greater));
+    /// assert_eq!(a.compare(&b, CompareMode::Dictionary), Ok(Ordering::Less));
+    /// assert!(a.compare(&b, CompareMode::Integer).is_err());
+    /// ```
+    pub fn compare(&self, other: &MoltValue, mode: CompareMode) -> Result<Ordering, ResultCode> {
+        match mode {
+            CompareMode::Ascii => Ok(self.compare_ascii(other)),
+            CompareMode::Nocase => Ok(self.compare_nocase(other)),
+            CompareMode::Dictionary => Ok(self.compare_dictionary(other)),
+            CompareMode::Integer => self.compare_int(other),
+            CompareMode::Real => self.compare_real(other),
+        }
+    }
+
+    /// Compares the values' string reps by Unicode code point.
+    pub fn compare_ascii(&self, other: &MoltValue) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+
+    /// Compares the values' string reps by Unicode code point, ignoring case.
+    pub fn compare_nocase(&self, other: &MoltValue) -> Ordering {
+        nocase_cmp(self.as_str(), other.as_str())
+    }
+
+    /// Compares the values' string reps in dictionary order.  See `dictionary_cmp`.
+    pub fn compare_dictionary(&self, other: &MoltValue) -> Ordering {
+        dictionary_cmp(self.as_str(), other.as_str())
+    }
+
+    /// Compares the values as integers, converting them if need be.  Returns an
+    /// error if either value isn't an integer.
+    pub fn compare_int(&self, other: &MoltValue) -> Result<Ordering, ResultCode> {
+        Ok(self.as_int()?.cmp(&other.as_int()?))
+    }
+
+    /// Compares the values as real numbers, converting them if need be.  Returns an
+    /// error if either value isn't a number, or is NaN, which can't be ordered.  A
+    /// value whose data rep is an integer is compared as that integer, and keeps its
+    /// data rep.
+    pub fn compare_real(&self, other: &MoltValue) -> Result<Ordering, ResultCode> {
+        let (a, b) = (real(self)?, real(other)?);
+        Ok(a.partial_cmp(&b).expect("NaN is rejected"))
+    }
+}
+
+// Returns the value as a real number, using its integer data rep if it has one.
+// It's an error if the value is NaN, as in TCL.
+fn real(value: &MoltValue) -> Result<MoltFloat, ResultCode> {
+    let flt = if value.rep_kind() == RepKind::Int {
+        value.as_int()? as MoltFloat
+    } else {
+        value.as_float()?
+    };
+
+    if flt.is_nan() {
+        return molt_err!("floating point value is Not a Number");
+    }
+
+    Ok(flt)
+}
+
+/// Compares two strings by Unicode code point, ignoring case.
+pub fn nocase_cmp(a: &str, b: &str) -> Ordering {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .cmp(b.chars().flat_map(char::to_lowercase))
+}
+
+/// Compares two strings in dictionary order, as TCL's `lsort -dictionary` does.
+///
+/// Case is ignored, except as a tie-breaker, in which case uppercase letters sort
+/// before lowercase letters.  Embedded runs of digits compare as integers, so that
+/// "x9y" sorts before "x10y"; if two numbers are equal, the one with fewer leading
+/// zeros sorts first, but again only as a tie-breaker.
+pub fn dictionary_cmp(a: &str, b: &str) -> Ordering {
+    let (mut left, mut right) = (a, b);
+
+    // The result if the strings are otherwise equal: the first difference in case
+    // or leading zeros.
+    let mut secondary = Ordering::Equal;
+
+    loop {
+        // FIRST, compare embedded numbers numerically.
+        if starts_with_digit(left) && starts_with_digit(right) {
+            let (lzeros, rzeros) = (leading_zeros(left), leading_zeros(right));
+            left = &left[lzeros..];
+            right = &right[rzeros..];
+
+            if secondary == Ordering::Equal {
+                secondary = lzeros.cmp(&rzeros);
+            }
+
+            // Without leading zeros, the longer number is larger.
+            let (ldigits, rdigits) = (digits(left), digits(right));
+            let order = ldigits
+                .cmp(&rdigits)
+                .then_with(|| left[..ldigits].cmp(&right[..rdigits]));
+
+            if order != Ordering::Equal {
+                return order;
+            }
+
+            left = &left[ldigits..];
+            right = &right[rdigits..];
+            continue;
+        }
+
+        // NEXT, compare characters, ignoring case.  If either string is exhausted,
+        // the shorter string sorts first.
+        let (lc, rc) = match (left.chars().next(), right.chars().next()) {
+            (Some(lc), Some(rc)) => (lc, rc),
+            (lc, rc) => return lc.is_some().cmp(&rc.is_some()).then(secondary),
+        };
+
+        if lc != rc {
+            let order = lower(lc).cmp(&lower(rc));
+
+            if order != Ordering::Equal {
+                return order;
+            }
+
+            if secondary == Ordering::Equal {
+                if lc.is_uppercase() && rc.is_lowercase() {
+                    secondary = Ordering::Less;
+                } else if lc.is_lowercase() && rc.is_uppercase() {
+                    secondary = Ordering::Greater;
+                }
+            }
+        }
+
+        left = &left[lc.len_utf8()..];
+        right = &right[rc.len_utf8()..];
+    }
+}
+
+// Does the string begin with an ASCII digit?
+fn starts_with_digit(str: &str) -> bool {
+    str.bytes().next().is_some_and(|b| b.is_ascii_digit())
+}
+
+// Returns the number of leading zeros in the string that are followed by another
+// digit.
+fn leading_zeros(str: &str) -> usize {
+    let bytes = str.as_bytes();
+    let mut i = 0;
+
+    while bytes[i] == b'0' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit) {
+        i += 1;
+    }
+
+    i
+}
+
+// Returns the number of leading ASCII digits in the string.
+fn digits(str: &str) -> usize {
+    str.bytes().take_while(u8::is_ascii_digit).count()
+}
+
+// Returns the character's lowercase form, if it has a single-character one.
+pub(crate) fn lower(c: char) -> char {
+    let mut lower = c.to_lowercase();
+
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::MoltInt;
+
+    fn val(str: &str) -> MoltValue {
+        MoltValue::from_string(str.to_string())
+    }
+
+    // Sorts the strings using the comparison function.
+    fn sorted(strs: &[&str], cmp: fn(&str, &str) -> Ordering) -> Vec<String> {
+        let mut out: Vec<String> = strs.iter().map(|s| s.to_string()).collect();
+        out.sort_by(|a, b| cmp(a, b));
+        out
+    }
+
+    #[test]
+    fn string_modes() {
+        assert_eq!(val("a").compare_ascii(&val("B")), Ordering::Greater);
+        assert_eq!(val("a").compare_nocase(&val("B")), Ordering::Less);
+        assert_eq!(val("ABC").compare_nocase(&val("abc")), Ordering::Equal);
+        assert_eq!(val("é").compare_ascii(&val("z")), Ordering::Greater);
+        assert_eq!(nocase_cmp("Straße", "STRASSE"), Ordering::Greater);
+        assert_eq!(nocase_cmp("ab", "ABC"), Ordering::Less);
+    }
+
+    #[test]
+    fn dictionary() {
+        // The examples from the TCL lsort man page.
+        assert_eq!(
+            sorted(&["bigboy", "bigBoy", "bigbang"], dictionary_cmp),
+            vec!["bigbang", "bigBoy", "bigboy"]
+        );
+        assert_eq!(
+            sorted(&["x11y", "x9y", "x10y"], dictionary_cmp),
+            vec!["x9y", "x10y", "x11y"]
+        );
+
+        // Case and leading zeros are tie-breakers.
+        assert_eq!(
+            sorted(&["a", "B", "A", "b"], dictionary_cmp),
+            vec!["A", "a", "B", "b"]
+        );
+        assert_eq!(
+            sorted(&["x01", "x1", "x2", "x001"], dictionary_cmp),
+            vec!["x1", "x01", "x001", "x2"]
+        );
+        assert_eq!(dictionary_cmp("A1b", "a1B"), Ordering::Less);
+        assert_eq!(dictionary_cmp("a01B", "a1b"), Ordering::Greater);
+
+        // Shorter strings sort first.
+        assert_eq!(dictionary_cmp("abc", "ab"), Ordering::Greater);
+        assert_eq!(dictionary_cmp("a1", "a1x"), Ordering::Less);
+        assert_eq!(dictionary_cmp("", ""), Ordering::Equal);
+        assert_eq!(dictionary_cmp("0", "00"), Ordering::Less);
+        assert_eq!(dictionary_cmp("abc", "abc"), Ordering::Equal);
+
+        // Numbers too large for a MoltInt still compare numerically.
+        assert_eq!(
+            dictionary_cmp("n99999999999999999999", "n100000000000000000000"),
+            Ordering::Less
+        );
+
+        assert_eq!(
+            val("Item12").compare(&val("item2"), CompareMode::Dictionary),
+            Ok(Ordering::Greater)
+        );
+    }
+
+    #[test]
+    fn numeric_modes() {
+        assert_eq!(val("10").compare_int(&val("9")), Ok(Ordering::Greater));
+        assert_eq!(val("0x10").compare_int(&val("16")), Ok(Ordering::Equal));
+        assert_eq!(
+            val("1").compare_int(&val("abc")),
+            molt_err!("expected integer but got \"abc\"")
+        );
+        assert_eq!(
+            val("1.5").compare(&val("2"), CompareMode::Integer),
+            molt_err!("expected integer but got \"1.5\"")
+        );
+
+        assert_eq!(val("1.5").compare_real(&val("10")), Ok(Ordering::Less));
+        assert_eq!(val("-0.0").compare_real(&val("0")), Ok(Ordering::Equal));
+        assert_eq!(
+            val("NaN").compare_real(&val("1")),
+            molt_err!("floating point value is Not a Number")
+        );
+        assert_eq!(
+            val("1").compare(&MoltValue::from_float(MoltFloat::NAN), CompareMode::Real),
+            molt_err!("floating point value is Not a Number")
+        );
+        assert_eq!(
+            val("x").compare(&val("1"), CompareMode::Real),
+            molt_err!("expected floating-point number but got \"x\"")
+        );
+
+        // Numeric comparisons reuse and cache the data reps.
+        let (a, b) = (val("12"), val("3"));
+        assert_eq!(a.compare_int(&b), Ok(Ordering::Greater));
+        assert_eq!(a.rep_kind(), RepKind::Int);
+        assert_eq!(a.compare_real(&b), Ok(Ordering::Greater));
+        assert_eq!(a.rep_kind(), RepKind::Int);
+
+        let c = MoltValue::from_int(MoltInt::MAX);
+        assert_eq!(c.compare_real(&val("1e300")), Ok(Ordering::Less));
+        assert!(!c.has_string_rep());
+    }
+
+    #[test]
+    fn compare_mode_display() {
+        assert_eq!(CompareMode::default(), CompareMode::Ascii);
+        assert_eq!(CompareMode::Dictionary.to_string(), "dictionary");
+        assert_eq!(CompareMode::Nocase.to_string(), "nocase");
+    }
+}
@@ -0,0 +1,238 @@
+//! Dictionaries
+//!
+//! This module defines `MoltDict`, the data rep for TCL dictionaries: an ordered map
+//! from keys to values, where keys are compared by their string reps.  Entries
+//! retain the order in which their keys were first added, as in standard TCL.
+//!
+//! It also contains the code that converts a string into a `MoltDict` and back again.
+//! A dictionary's string rep is a list of alternating keys and values; any list with
+//! an even number of elements can be interpreted as a dictionary.  These are used
+//! by `MoltValue` when shimmering between a value's string rep and a `Datum::Dict`;
+//! client code should normally use `MoltValue` instead.
+
+use crate::list;
+use crate::value::MoltValue;
+use crate::value::ResultCode;
+use std::collections::HashMap;
+use std::iter;
+use std::iter::FromIterator;
+
+/// An insertion-ordered map from keys to values, keyed by the keys' string reps.
+///
+/// The keys and values are normally `MoltValue`s, but may be any type that has a
+/// string rep, e.g., `SyncMoltValue`.
+#[derive(Clone, Debug)]
+pub struct MoltDict<V = MoltValue> {
+    // The key/value pairs, in insertion order.
+    entries: Vec<(V, V)>,
+
+    // A map from key string to index in `entries`.
+    index: HashMap<String, usize>,
+}
+
+impl<V> Default for MoltDict<V> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<V: AsRef<str>> MoltDict<V> {
+    /// Creates a new, empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of entries in the dictionary.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the dictionary has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds an entry to the dictionary.  If the key is already present, its value
+    /// is replaced but it retains its original position.
+    pub fn insert(&mut self, key: V, value: V) {
+        if let Some(&i) = self.index.get(key.as_ref()) {
+            self.entries[i].1 = value;
+        } else {
+            self.index
+                .insert(key.as_ref().to_string(), self.entries.len());
+            self.entries.push((key, value));
+        }
+    }
+
+    /// Returns the value for the given key, if any.
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.index.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    /// Returns true if the dictionary contains the given key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Removes the entry with the given key, returning its value, if any.  The
+    /// remaining entries retain their order.
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        let i = self.index.remove(key)?;
+        let (_, value) = self.entries.remove(i);
+
+        for idx in self.index.values_mut() {
+            if *idx > i {
+                *idx -= 1;
+            }
+        }
+
+        Some(value)
+    }
+
+    /// Returns an iterator over the key/value pairs, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&V, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values, in key order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl<V: AsRef<str>> FromIterator<(V, V)> for MoltDict<V> {
+    fn from_iter<I: IntoIterator<Item = (V, V)>>(iter: I) -> Self {
+        let mut dict = MoltDict::new();
+
+        for (key, value) in iter {
+            dict.insert(key, value);
+        }
+
+        dict
+    }
+}
+
+/// Parses a string into a `MoltDict`.  The string must be a valid TCL list with
+/// an even number of elements; if a key appears more than once, the last value wins.
+pub fn get_dict(str: &str) -> Result<MoltDict, ResultCode> {
+    let list = list::get_list(str)?;
+
+    if list.len() % 2 != 0 {
+        return molt_err!("missing value to go with key");
+    }
+
+    let mut dict = MoltDict::new();
+    let mut items = list.into_iter();
+
+    while let (Some(key), Some(value)) = (items.next(), items.next()) {
+        dict.insert(key, value);
+    }
+
+    Ok(dict)
+}
+
+/// Formats a dictionary as a string: a TCL list of alternating keys and values.
+pub fn dict_to_string<V: AsRef<str>>(dict: &MoltDict<V>) -> String {
+    list::format_list(
+        dict.entries
+            .iter()
+            .flat_map(|(k, v)| iter::once(k).chain(iter::once(v))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn val(str: &str) -> MoltValue {
+        MoltValue::from_string(str.to_string())
+    }
+
+    // Returns the dictionary's entries as pairs of strings.
+    fn pairs(dict: &MoltDict) -> Vec<(String, String)> {
+        dict.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut dict = MoltDict::new();
+        assert!(dict.is_empty());
+
+        dict.insert(val("b"), val("1"));
+        dict.insert(val("a"), val("2"));
+        dict.insert(MoltValue::from_int(3), val("3"));
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.get("a").map(|v| v.to_string()), Some("2".to_string()));
+        assert_eq!(dict.get("3").map(|v| v.to_string()), Some("3".to_string()));
+        assert!(dict.get("c").is_none());
+        assert!(dict.contains_key("b"));
+
+        // Replacing a value retains the key's position.
+        dict.insert(val("b"), val("4"));
+        assert_eq!(
+            pairs(&dict),
+            vec![
+                ("b".to_string(), "4".to_string()),
+                ("a".to_string(), "2".to_string()),
+                ("3".to_string(), "3".to_string())
+            ]
+        );
+
+        // Removing an entry retains the order of the others.
+        assert_eq!(
+            dict.remove("b").map(|v| v.to_string()),
+            Some("4".to_string())
+        );
+        assert!(dict.remove("b").is_none());
+        assert_eq!(dict.get("3").map(|v| v.to_string()), Some("3".to_string()));
+        assert_eq!(
+            dict.keys().map(|k| k.to_string()).collect::<Vec<_>>(),
+            vec!["a".to_string(), "3".to_string()]
+        );
+    }
+
+    #[test]
+    fn get_dict_parses() {
+        let dict = get_dict("a 1 {b c} {2 3} a 4").unwrap();
+        assert_eq!(
+            pairs(&dict),
+            vec![
+                ("a".to_string(), "4".to_string()),
+                ("b c".to_string(), "2 3".to_string())
+            ]
+        );
+
+        assert!(get_dict("").unwrap().is_empty());
+        assert_eq!(
+            get_dict("a 1 b").map(|_| ()),
+            molt_err!("missing value to go with key")
+        );
+        assert_eq!(
+            get_dict("a {1").map(|_| ()),
+            molt_err!("unmatched open brace in list")
+        );
+    }
+
+    #[test]
+    fn dict_to_string_formats() {
+        let mut dict = MoltDict::new();
+        assert_eq!(dict_to_string(&dict), "");
+
+        dict.insert(val("a"), val("1"));
+        dict.insert(val("b c"), val(""));
+        assert_eq!(dict_to_string(&dict), "a 1 {b c} {}");
+
+        let dict2 = get_dict(&dict_to_string(&dict)).unwrap();
+        assert_eq!(pairs(&dict2), pairs(&dict));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::value::MoltType;
 use crate::value::MoltValue;
+use crate::value::ResultCode;
 use std::fmt;
 use std::rc::Rc;
 use std::str::FromStr;
@@ -10,13 +12,25 @@ pub enum Flavor {
 }
 
 impl Flavor {
-    // TODO: The error should be a Molt ResultCode.
-    pub fn from_molt(value: &MoltValue) -> Result<Rc<Self>, String> {
-        if let Some(x) = value.as_other::<Flavor>() {
-            Ok(x)
-        } else {
-            Err("Not a flavor string".to_string())
-        }
+    pub fn from_molt(value: &MoltValue) -> Result<Rc<Self>, ResultCode> {
+        value.as_other::<Flavor>()
+    }
+}
+
+impl MoltType for Flavor {
+    type Err = String;
+
+    fn type_name() -> &'static str {
+        "flavor"
+    }
+
+    fn to_molt_string(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+        str.parse()
+            .map_err(|_| format!("expected flavor but got \"{}\"", str))
     }
 }
 
@@ -64,5 +78,11 @@ mod tests {
         let out = Flavor::from_molt(&value);
 
         assert_eq!(*(out.unwrap()), Flavor::SALTY);
+
+        let value = MoltValue::from_string("sour".to_string());
+        assert_eq!(
+            Flavor::from_molt(&value),
+            molt_err!("expected flavor but got \"sour\"")
+        );
     }
 }
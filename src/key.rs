@@ -0,0 +1,245 @@
+//! Value Keys
+//!
+//! `MoltValue` deliberately doesn't implement `PartialEq`, `Eq`, or `Hash`: two values
+//! are the same TCL value if their string reps are the same, and Rust code should
+//! normally extract the data in the desired form and compare that.  But values are
+//! often needed as keys, e.g., in a `HashMap` or `HashSet`, or when finding the
+//! unique elements of a list.
+//!
+//! The [`MoltKey`] newtype wraps a `MoltValue` and implements `Eq`, `Hash`, and `Ord`
+//! by string rep.  Comparisons avoid allocating where possible: values that share
+//! their string rep are equal without comparing their strings, and integers with no
+//! string rep are compared and hashed without producing one.  `MoltKey` implements
+//! `Borrow<str>`, so collections of keys can be searched using a `&str`.
+//!
+//! A key's string rep never changes, so it's safe to use as a key even though
+//! `MoltValue` caches its reps using interior mutability; Clippy's
+//! `mutable_key_type` lint can be allowed for collections of keys.
+//!
+//! [`MoltKey`]: struct.MoltKey.html
+
+use crate::value::MoltInt;
+use crate::value::MoltValue;
+use crate::value::RepKind;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// A `MoltValue` that is compared, hashed, and ordered by its string rep.
+#[derive(Clone, Debug)]
+pub struct MoltKey(MoltValue);
+
+impl MoltKey {
+    /// Creates a new key from a value.
+    pub fn new(value: MoltValue) -> Self {
+        MoltKey(value)
+    }
+
+    /// Returns the key's value.
+    pub fn value(&self) -> &MoltValue {
+        &self.0
+    }
+
+    /// Returns the key's value, consuming the key.
+    pub fn into_value(self) -> MoltValue {
+        self.0
+    }
+
+    /// Returns the key's integer value if it is an integer with no string rep, so
+    /// that its string rep is the integer's canonical string.
+    fn pure_int(&self) -> Option<MoltInt> {
+        if !self.0.has_string_rep() && self.0.rep_kind() == RepKind::Int {
+            self.0.as_int().ok()
+        } else {
+            None
+        }
+    }
+}
+
+impl From<MoltValue> for MoltKey {
+    fn from(value: MoltValue) -> Self {
+        MoltKey(value)
+    }
+}
+
+impl Borrow<str> for MoltKey {
+    fn borrow(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Display for MoltKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+impl PartialEq for MoltKey {
+    fn eq(&self, other: &Self) -> bool {
+        // FIRST, compare integers without string reps directly.
+        match (self.pure_int(), other.pure_int()) {
+            (Some(a), Some(b)) => return a == b,
+            (Some(int), None) => return other.0.as_str() == format_int(int, &mut [0; 20]),
+            (None, Some(int)) => return self.0.as_str() == format_int(int, &mut [0; 20]),
+            (None, None) => (),
+        }
+
+        // NEXT, values that share their string rep are equal.
+        let (a, b) = (self.0.as_str(), other.0.as_str());
+        std::ptr::eq(a, b) || a == b
+    }
+}
+
+impl Eq for MoltKey {}
+
+// Keys hash exactly as their string reps do, as `Borrow<str>` requires.
+impl Hash for MoltKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self.pure_int() {
+            Some(int) => format_int(int, &mut [0; 20]).hash(state),
+            None => self.0.as_str().hash(state),
+        }
+    }
+}
+
+impl PartialOrd for MoltKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Keys are ordered by their string reps.
+impl Ord for MoltKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (mut abuf, mut bbuf) = ([0; 20], [0; 20]);
+
+        let a = match self.pure_int() {
+            Some(int) => format_int(int, &mut abuf),
+            None => self.0.as_str(),
+        };
+
+        let b = match other.pure_int() {
+            Some(int) => format_int(int, &mut bbuf),
+            None => other.0.as_str(),
+        };
+
+        a.cmp(b)
+    }
+}
+
+/// Formats an integer in decimal into the buffer, which is large enough for any
+/// `MoltInt`, and returns the formatted string.
+fn format_int(int: MoltInt, buf: &mut [u8; 20]) -> &str {
+    let mut n = int.unsigned_abs();
+    let mut i = buf.len();
+
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+
+        if n == 0 {
+            break;
+        }
+    }
+
+    if int < 0 {
+        i -= 1;
+        buf[i] = b'-';
+    }
+
+    std::str::from_utf8(&buf[i..]).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::collections::HashSet;
+
+    fn key(str: &str) -> MoltKey {
+        MoltKey::new(MoltValue::from_string(str.to_string()))
+    }
+
+    fn int(int: MoltInt) -> MoltKey {
+        MoltKey::new(MoltValue::from_int(int))
+    }
+
+    fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn format_int_formats() {
+        for &n in &[0, 7, -7, 10, 1234567890, MoltInt::MAX, MoltInt::MIN] {
+            assert_eq!(format_int(n, &mut [0; 20]), n.to_string());
+        }
+    }
+
+    #[test]
+    fn eq_and_hash() {
+        // Keys are equal if their string reps are.
+        assert_eq!(key("abc"), key("abc"));
+        assert_ne!(key("abc"), key("abd"));
+        assert_eq!(key("5"), int(5));
+        assert_eq!(int(5), key("5"));
+        assert_eq!(int(-5), int(-5));
+        assert_ne!(int(5), int(6));
+        assert_ne!(key("05"), int(5));
+
+        // A value that was parsed as an integer keeps its own string rep.
+        let parsed = MoltValue::from_string("0x10".to_string());
+        parsed.as_int().unwrap();
+        assert_ne!(MoltKey::new(parsed), int(16));
+
+        // Comparing integers doesn't produce their string reps.
+        let a = int(42);
+        assert_eq!(a, int(42));
+        assert!(!a.value().has_string_rep());
+
+        // Keys hash as their string reps do.
+        assert_eq!(hash(&key("abc")), hash("abc"));
+        assert_eq!(hash(&int(-42)), hash("-42"));
+        assert_eq!(hash(&int(42)), hash(&key("42")));
+        assert!(!a.value().has_string_rep());
+
+        // Clones share their string reps.
+        let b = key("a b c");
+        assert_eq!(b.clone(), b);
+    }
+
+    #[test]
+    fn ord() {
+        let mut keys = [int(9), key("a"), int(10), key("")];
+        keys.sort();
+        let strings: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        assert_eq!(strings, vec!["", "10", "9", "a"]);
+        assert_eq!(int(-1).cmp(&int(-2)), Ordering::Less);
+        assert_eq!(int(3).cmp(&key("3")), Ordering::Equal);
+    }
+
+    // A key's interior mutability only caches reps; it never changes its string rep.
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn collections() {
+        let mut set = HashSet::new();
+        set.insert(key("1"));
+        set.insert(int(1));
+        set.insert(int(2));
+        set.insert(MoltKey::from(MoltValue::from_float(1.0)));
+        assert_eq!(set.len(), 3);
+
+        let mut map = HashMap::new();
+        map.insert(int(1), "one");
+        map.insert(key("two"), "two");
+        assert_eq!(map.get("1"), Some(&"one"));
+        assert_eq!(map.get("two"), Some(&"two"));
+        assert_eq!(map.get(&key("1")), Some(&"one"));
+        assert_eq!(map.get("3"), None);
+    }
+}
@@ -1,6 +1,14 @@
 // Because this is experimental.
 #![allow(dead_code)]
 
+/// Returns an `Err(ResultCode::Error)` whose value is the formatted message.
+#[macro_export]
+macro_rules! molt_err {
+    ($($arg:tt)*) => (
+        Err($crate::value::ResultCode::Error($crate::value::MoltValue::from_string(format!($($arg)*))))
+    )
+}
+
 // Initial attempt at MyValue struct in March 2019.
 // How not to do it; this is a complete mess.
 mod value1;
@@ -75,3 +83,30 @@ mod flavor;
 
 // Preparing for integration
 pub mod value;
+
+// TCL list parsing and formatting, for shimmering string reps to and from lists.
+pub mod list;
+
+// TCL dictionaries, and their parsing and formatting.
+pub mod dict;
+
+// A registry of external types by name, for runtime conversions.
+pub mod registry;
+
+// A wrapper that lets values be used as keys in collections.
+pub mod key;
+
+// Comparing values as strings, in dictionary order, or as numbers.
+pub mod compare;
+
+// Sorting list values, like TCL's lsort.
+pub mod lsort;
+
+// Searching list values, like TCL's lsearch.
+pub mod lsearch;
+
+// A thread-safe MoltValue, for sharing values between threads.
+pub mod sync_value;
+
+// Shimmer statistics, when the "instrument" feature is enabled.
+pub mod stats;
@@ -0,0 +1,493 @@
+//! List Parsing and Formatting
+//!
+//! This module contains the code that converts a string into a `MoltList`, following
+//! the standard TCL list syntax: elements are separated by whitespace, and may be
+//! quoted with braces or double quotes.  Braced elements are taken literally;
+//! quoted and bare elements are subject to backslash substitution.
+//!
+//! It also contains the code that converts a `MoltList` back into its canonical
+//! string rep, quoting each element just enough that parsing the string yields
+//! the same elements.
+//!
+//! These are used by `MoltValue` when shimmering between a value's string rep and
+//! a `Datum::List`; client code should normally use `MoltValue` instead.
+
+use crate::value::MoltList;
+use crate::value::MoltValue;
+use crate::value::ResultCode;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Parses a string into a `MoltList`, following the TCL list syntax.
+///
+/// Returns an error if the string is not a valid TCL list, e.g., if it contains an
+/// unmatched open brace.
+pub fn get_list(str: &str) -> Result<MoltList, ResultCode> {
+    let mut chars = str.chars().peekable();
+    let mut list = Vec::new();
+
+    // FIRST, skip any leading whitespace.
+    skip_whitespace(&mut chars);
+
+    // NEXT, parse elements until we run out of input.
+    while chars.peek().is_some() {
+        let element = match chars.peek() {
+            Some('{') => parse_braced_item(&mut chars)?,
+            Some('"') => parse_quoted_item(&mut chars)?,
+            _ => parse_bare_item(&mut chars),
+        };
+
+        list.push(MoltValue::from_string(element));
+        skip_whitespace(&mut chars);
+    }
+
+    Ok(list)
+}
+
+/// Formats a list as a string, following the TCL list syntax.  Each element is
+/// quoted with braces or backslashes only if necessary, so that `get_list` will
+/// return the same elements.
+/// The elements may be `MoltValue`s or any other values that have string reps.
+pub fn list_to_string<V: AsRef<str>>(list: &[V]) -> String {
+    format_list(list.iter())
+}
+
+/// Formats a sequence of values as a TCL list; this allows types like `MoltDict`
+/// to produce a list string without first building a `MoltList`.
+pub(crate) fn format_list<'a, I, V>(items: I) -> String
+where
+    I: Iterator<Item = &'a V>,
+    V: AsRef<str> + 'a,
+{
+    let mut out = String::new();
+
+    for (i, value) in items.enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+        format_element(value.as_ref(), i == 0, &mut out);
+    }
+
+    out
+}
+
+/// Appends a single list element to the output, quoting it as needed.  As in
+/// standard TCL, a leading "#" is quoted only in the first element, so that the
+/// list can't be mistaken for a comment.
+fn format_element(element: &str, is_first: bool, out: &mut String) {
+    // FIRST, the empty element must be quoted with braces.
+    if element.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+
+    // NEXT, if the element needs no quoting, just add it.
+    let needs_hash_quote = is_first && element.starts_with('#');
+
+    if !needs_hash_quote && !element.chars().any(needs_quoting) {
+        out.push_str(element);
+        return;
+    }
+
+    // NEXT, use braces if they will preserve the element exactly.
+    if can_brace(element) {
+        out.push('{');
+        out.push_str(element);
+        out.push('}');
+        return;
+    }
+
+    // NEXT, use backslashes.
+    if needs_hash_quote {
+        out.push('\\');
+    }
+
+    for c in element.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '\x0B' => out.push_str("\\v"),
+            '\x0C' => out.push_str("\\f"),
+            _ => {
+                if needs_quoting(c) {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+        }
+    }
+}
+
+/// Is this a character that requires the list element containing it to be
+/// quoted?
+fn needs_quoting(c: char) -> bool {
+    is_list_white(c) || matches!(c, '{' | '}' | '[' | ']' | '$' | ';' | '"' | '\\')
+}
+
+/// Can the element be quoted with braces?  This is the case if its braces
+/// are balanced, it doesn't end with a backslash that would escape the closing
+/// brace, and it contains no backslash-newline, which TCL substitutes even within
+/// braces when the list is evaluated as a command.
+fn can_brace(element: &str) -> bool {
+    let mut depth = 0;
+    let mut chars = element.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                if depth == 0 {
+                    return false;
+                }
+                depth -= 1;
+            }
+            '\\' => match chars.next() {
+                None | Some('\n') => return false,
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+
+    depth == 0
+}
+
+/// Parses a braced list element, returning its content literally.  The
+/// open brace is the next character in the input.
+fn parse_braced_item(chars: &mut Peekable<Chars>) -> Result<String, ResultCode> {
+    // FIRST, skip the opening brace, and count it.
+    chars.next();
+    let mut count = 1;
+    let mut item = String::new();
+
+    // NEXT, add characters to the item until we find the matching close brace.
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                count += 1;
+                item.push(c);
+            }
+            '}' => {
+                count -= 1;
+
+                if count == 0 {
+                    check_end_of_item(chars, "braces")?;
+                    return Ok(item);
+                }
+
+                item.push(c);
+            }
+            '\\' => {
+                // A backslash prevents the following character from counting as a
+                // brace, but both are retained.
+                item.push(c);
+
+                if let Some(next) = chars.next() {
+                    item.push(next);
+                }
+            }
+            _ => item.push(c),
+        }
+    }
+
+    molt_err!("unmatched open brace in list")
+}
+
+/// Parses a quoted list element, doing backslash substitution.  The open
+/// quote is the next character in the input.
+fn parse_quoted_item(chars: &mut Peekable<Chars>) -> Result<String, ResultCode> {
+    // FIRST, skip the opening quote.
+    chars.next();
+    let mut item = String::new();
+
+    // NEXT, add characters to the item until we find the close quote.
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                check_end_of_item(chars, "quotes")?;
+                return Ok(item);
+            }
+            '\\' => item.push_str(&subst_backslash(chars)),
+            _ => item.push(c),
+        }
+    }
+
+    molt_err!("unmatched open quote in list")
+}
+
+/// Parses a bare list element, doing backslash substitution.  The element ends
+/// at the next unescaped whitespace character, or at the end of the input.
+fn parse_bare_item(chars: &mut Peekable<Chars>) -> String {
+    let mut item = String::new();
+
+    while let Some(&c) = chars.peek() {
+        if is_list_white(c) {
+            break;
+        }
+
+        chars.next();
+
+        if c == '\\' {
+            item.push_str(&subst_backslash(chars));
+        } else {
+            item.push(c);
+        }
+    }
+
+    item
+}
+
+/// Verifies that a braced or quoted element is followed by whitespace or the end of
+/// the input.
+fn check_end_of_item(chars: &mut Peekable<Chars>, quoting: &str) -> Result<(), ResultCode> {
+    match chars.peek() {
+        Some(&c) if !is_list_white(c) => {
+            // As in standard TCL, show at most 20 characters of the offending text.
+            let rest: String = chars
+                .clone()
+                .take_while(|ch| !is_list_white(*ch))
+                .take(20)
+                .collect();
+            molt_err!(
+                "list element in {} followed by \"{}\" instead of space",
+                quoting,
+                rest
+            )
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Skips over whitespace in the input.
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while let Some(&c) = chars.peek() {
+        if !is_list_white(c) {
+            break;
+        }
+        chars.next();
+    }
+}
+
+/// Is the character whitespace, from the point of view of list syntax?
+fn is_list_white(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0B' | '\x0C')
+}
+
+/// Performs backslash substitution.  The backslash itself has already been
+/// consumed; the remainder of the sequence is read from the input, and the
+/// substituted text is returned.
+pub(crate) fn subst_backslash(chars: &mut Peekable<Chars>) -> String {
+    let c = match chars.next() {
+        Some(c) => c,
+        // A trailing backslash is taken literally.
+        None => return "\\".to_string(),
+    };
+
+    let substituted = match c {
+        'a' => '\x07',
+        'b' => '\x08',
+        'f' => '\x0C',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'v' => '\x0B',
+        '\n' => {
+            // Backslash-newline and any following spaces and tabs become a single space.
+            while let Some(' ') | Some('\t') = chars.peek() {
+                chars.next();
+            }
+            ' '
+        }
+        '0'..='7' => {
+            let mut code = c.to_digit(8).unwrap();
+            let mut count = 1;
+
+            while count < 3 {
+                match chars.peek().and_then(|ch| ch.to_digit(8)) {
+                    Some(digit) => {
+                        code = code * 8 + digit;
+                        chars.next();
+                        count += 1;
+                    }
+                    None => break,
+                }
+            }
+
+            // Only eight bits are significant, as in standard TCL.
+            char::from((code & 0xFF) as u8)
+        }
+        'x' => match read_hex(chars, 2) {
+            Some(code) => char::from(code as u8),
+            None => 'x',
+        },
+        'u' => match read_hex(chars, 4) {
+            Some(code) => std::char::from_u32(code).unwrap_or('\u{FFFD}'),
+            None => 'u',
+        },
+        'U' => match read_hex(chars, 8) {
+            Some(code) => std::char::from_u32(code).unwrap_or('\u{FFFD}'),
+            None => 'U',
+        },
+        _ => c,
+    };
+
+    substituted.to_string()
+}
+
+/// Reads up to `max` hex digits from the input, returning the value, or None
+/// if there are no hex digits.
+fn read_hex(chars: &mut Peekable<Chars>, max: usize) -> Option<u32> {
+    let mut code: u32 = 0;
+    let mut count = 0;
+
+    while count < max {
+        match chars.peek().and_then(|ch| ch.to_digit(16)) {
+            Some(digit) => {
+                code = code.wrapping_mul(16).wrapping_add(digit);
+                chars.next();
+                count += 1;
+            }
+            None => break,
+        }
+    }
+
+    if count > 0 {
+        Some(code)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parses the string as a list, and returns the elements as strings.
+    fn parse(str: &str) -> Result<Vec<String>, ResultCode> {
+        let list = get_list(str)?;
+        Ok(list.iter().map(|v| v.to_string()).collect())
+    }
+
+    // Formats the strings as a list.
+    fn format(items: &[&str]) -> String {
+        let list: MoltList = items
+            .iter()
+            .map(|s| MoltValue::from_string(s.to_string()))
+            .collect();
+        list_to_string(&list)
+    }
+
+    // Formats the strings as a list, parses the result, and verifies that the
+    // elements are unchanged.
+    fn round_trip(items: &[&str]) {
+        let str = format(items);
+        let expected: Vec<String> = items.iter().map(|s| s.to_string()).collect();
+        assert_eq!(parse(&str), Ok(expected), "list string: {:?}", str);
+    }
+
+    #[test]
+    fn list_to_string_canonical() {
+        assert_eq!(format(&[]), "");
+        assert_eq!(format(&["a", "b", "c"]), "a b c");
+        assert_eq!(format(&["a", "", "c"]), "a {} c");
+        assert_eq!(format(&["a b", "c"]), "{a b} c");
+        assert_eq!(format(&["a {b c}"]), "{a {b c}}");
+        assert_eq!(format(&["a\nb"]), "{a\nb}");
+        assert_eq!(format(&["a\n b"]), "{a\n b}");
+        assert_eq!(format(&["#a", "#b"]), "{#a} #b");
+        assert_eq!(format(&["$x", "[y]", "a;b"]), "{$x} {[y]} {a;b}");
+        assert_eq!(format(&["a{b"]), "a\\{b");
+        assert_eq!(format(&["a} b"]), "a\\}\\ b");
+        assert_eq!(format(&["a\\"]), "a\\\\");
+        assert_eq!(format(&["a{\nb"]), "a\\{\\nb");
+        assert_eq!(format(&["a\\\nb"]), "a\\\\\\nb");
+        assert_eq!(format(&["a\\\nb c"]), "a\\\\\\nb\\ c");
+    }
+
+    #[test]
+    fn list_to_string_round_trip() {
+        round_trip(&[]);
+        round_trip(&["a", "b c", ""]);
+        round_trip(&["", "", ""]);
+        round_trip(&["{", "}", "}{", "{a}}", "{{a}"]);
+        round_trip(&["a\\", "\\", "a\\ b", "\\{"]);
+        round_trip(&["\"", "a\"b", "\"a b\""]);
+        round_trip(&["#", "#a b", "a #b"]);
+        round_trip(&["line1\nline2", "\n", "a\tb{\n"]);
+        round_trip(&["a\\\nb", "\\\n", "{a\\\n}"]);
+        round_trip(&["{a b} c", "[x] $y;"]);
+        round_trip(&["été", "a\u{00A0}b"]);
+    }
+
+    #[test]
+    fn get_list_simple() {
+        assert_eq!(parse(""), Ok(vec![]));
+        assert_eq!(parse("   \n\t "), Ok(vec![]));
+        assert_eq!(parse("a"), Ok(vec!["a".to_string()]));
+        assert_eq!(
+            parse("  a  b\tc\nd  "),
+            Ok(vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "d".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn get_list_braces() {
+        assert_eq!(
+            parse("a {b c} {}"),
+            Ok(vec!["a".to_string(), "b c".to_string(), "".to_string()])
+        );
+        assert_eq!(parse("{a {b c} d}"), Ok(vec!["a {b c} d".to_string()]));
+        assert_eq!(parse("{a\\nb}"), Ok(vec!["a\\nb".to_string()]));
+        assert_eq!(parse("{a\\}b}"), Ok(vec!["a\\}b".to_string()]));
+        assert_eq!(parse("{a \"b\"}"), Ok(vec!["a \"b\"".to_string()]));
+
+        assert_eq!(parse("a {b c"), molt_err!("unmatched open brace in list"));
+        assert_eq!(
+            parse("{a}b c"),
+            molt_err!("list element in braces followed by \"b\" instead of space")
+        );
+    }
+
+    #[test]
+    fn get_list_quotes() {
+        assert_eq!(
+            parse("a \"b c\" \"\""),
+            Ok(vec!["a".to_string(), "b c".to_string(), "".to_string()])
+        );
+        assert_eq!(parse("\"a\\tb\""), Ok(vec!["a\tb".to_string()]));
+        assert_eq!(parse("\"a {b\""), Ok(vec!["a {b".to_string()]));
+
+        assert_eq!(parse("a \"b c"), molt_err!("unmatched open quote in list"));
+        assert_eq!(
+            parse("\"a\"bc d"),
+            molt_err!("list element in quotes followed by \"bc\" instead of space")
+        );
+        assert_eq!(
+            parse("{a}bcdefghijklmnopqrstuvwxyz"),
+            molt_err!(
+                "list element in braces followed by \"bcdefghijklmnopqrstu\" instead of space"
+            )
+        );
+    }
+
+    #[test]
+    fn get_list_backslashes() {
+        assert_eq!(
+            parse("a\\ b c"),
+            Ok(vec!["a b".to_string(), "c".to_string()])
+        );
+        assert_eq!(parse("a\\nb"), Ok(vec!["a\nb".to_string()]));
+        assert_eq!(parse("\\u00e9t\\u00E9"), Ok(vec!["été".to_string()]));
+        assert_eq!(parse("\\x41\\101"), Ok(vec!["AA".to_string()]));
+        assert_eq!(parse("\\{a"), Ok(vec!["{a".to_string()]));
+        assert_eq!(parse("a\\"), Ok(vec!["a\\".to_string()]));
+        assert_eq!(parse("\\q"), Ok(vec!["q".to_string()]));
+        assert_eq!(parse("\"a\\\n   b\""), Ok(vec!["a b".to_string()]));
+    }
+}
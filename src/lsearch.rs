@@ -0,0 +1,635 @@
+//! List Searching
+//!
+//! This module implements TCL's `lsearch`: searching a list value for elements
+//! that match a pattern, by glob matching, by exact comparison using any of the
+//! comparison modes defined in the `compare` module, or by binary search of a
+//! sorted list.  See [`lsearch`] and [`SearchOptions`].
+//!
+//! The list's elements are retrieved from its list data rep, so a list that has
+//! already been parsed isn't parsed again; and the results are built from the
+//! elements themselves.
+//!
+//! [`lsearch`]: fn.lsearch.html
+//! [`SearchOptions`]: struct.SearchOptions.html
+
+use crate::compare;
+use crate::compare::CompareMode;
+use crate::lsort::nested_element;
+use crate::value::MoltInt;
+use crate::value::MoltList;
+use crate::value::MoltResult;
+use crate::value::MoltValue;
+use crate::value::ResultCode;
+use std::cmp::Ordering;
+
+/// How `lsearch` matches elements against the pattern.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    /// The pattern is a glob pattern; see `glob_match`.  This is the default.
+    #[default]
+    Glob,
+
+    /// The element must compare equal to the pattern in the comparison mode.
+    Exact,
+
+    /// As `Exact`, but the list is known to be sorted in the comparison mode, and
+    /// is searched by binary search.
+    Sorted,
+}
+
+/// The options for `lsearch`.  The defaults find the index of the first element
+/// that matches the pattern as a glob pattern.
+///
+/// # Example
+///
+/// ```
+/// use value::compare::CompareMode;
+/// use value::lsearch::lsearch;
+/// use value::lsearch::SearchOptions;
+/// use value::value::MoltValue;
+///
+/// let list = MoltValue::from_string("1 3 5 5 7".to_string());
+/// let pattern = MoltValue::from_int(5);
+/// let options = SearchOptions::new().sorted().mode(CompareMode::Integer).all();
+/// assert_eq!(lsearch(&list, &pattern, &options).unwrap().as_str(), "2 3");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SearchOptions {
+    match_mode: MatchMode,
+    mode: CompareMode,
+    decreasing: bool,
+    all: bool,
+    inline: bool,
+    negate: bool,
+    start: usize,
+    index: Vec<usize>,
+}
+
+impl SearchOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches elements as glob patterns.  This is the default.
+    pub fn glob(mut self) -> Self {
+        self.match_mode = MatchMode::Glob;
+        self
+    }
+
+    /// Matches elements that compare equal to the pattern.
+    pub fn exact(mut self) -> Self {
+        self.match_mode = MatchMode::Exact;
+        self
+    }
+
+    /// Matches elements that compare equal to the pattern, by binary search; the
+    /// list must be sorted in the comparison mode.  If several elements match, the
+    /// first of them is found.
+    pub fn sorted(mut self) -> Self {
+        self.match_mode = MatchMode::Sorted;
+        self
+    }
+
+    /// Sets the comparison mode, for exact and sorted matching.  The default is
+    /// `CompareMode::Ascii`.  Glob matching ignores case if the mode is
+    /// `CompareMode::Nocase`, and is otherwise unaffected.
+    pub fn mode(mut self, mode: CompareMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// For sorted matching, the list is sorted in decreasing order.
+    pub fn decreasing(mut self) -> Self {
+        self.decreasing = true;
+        self
+    }
+
+    /// Finds all matching elements, rather than just the first.
+    pub fn all(mut self) -> Self {
+        self.all = true;
+        self
+    }
+
+    /// Returns the matching elements, rather than their indices.
+    pub fn inline(mut self) -> Self {
+        self.inline = true;
+        self
+    }
+
+    /// Finds the elements that don't match, as TCL's `-not` does.  Sorted matching
+    /// becomes exact matching.
+    pub fn negate(mut self) -> Self {
+        self.negate = true;
+        self
+    }
+
+    /// Starts searching at the given index.
+    pub fn start(mut self, start: usize) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Matches the given element of each element against the pattern, treating each
+    /// as a list.  The path indexes into nested lists, as for `SortOptions::index`.
+    pub fn index(mut self, path: &[usize]) -> Self {
+        self.index = path.to_vec();
+        self
+    }
+
+    // Compares the element to the pattern, for exact and sorted matching.
+    fn compare(&self, elem: &MoltValue, pattern: &MoltValue) -> Result<Ordering, ResultCode> {
+        let order = self.key(elem, |key| key.compare(pattern, self.mode))?;
+
+        Ok(if self.decreasing {
+            order.reverse()
+        } else {
+            order
+        })
+    }
+
+    // Does the element match the pattern?
+    fn matches(&self, elem: &MoltValue, pattern: &MoltValue) -> Result<bool, ResultCode> {
+        let matched = match self.match_mode {
+            MatchMode::Glob => self.key(elem, |key| {
+                let nocase = self.mode == CompareMode::Nocase;
+                Ok(glob_match(pattern.as_str(), key.as_str(), nocase))
+            })?,
+            _ => self.compare(elem, pattern)? == Ordering::Equal,
+        };
+
+        Ok(matched != self.negate)
+    }
+
+    // Calls the function with the element's match key: the element itself, or the
+    // element at the index path.
+    fn key<R>(
+        &self,
+        elem: &MoltValue,
+        f: impl FnOnce(&MoltValue) -> Result<R, ResultCode>,
+    ) -> Result<R, ResultCode> {
+        if self.index.is_empty() {
+            f(elem)
+        } else {
+            f(&nested_element(elem, &self.index)?)
+        }
+    }
+}
+
+/// Searches the list value for elements that match the pattern, as the options
+/// require.  By default, returns the index of the first matching element, or -1 if
+/// there is none.  With `all`, returns a list of the indices of all matching
+/// elements.  With `inline`, returns the matching element, or the empty string, or
+/// with `all`, a list of the matching elements.
+///
+/// It's an error if the value isn't a list, if an element has no element at the
+/// index path, or if an element or the pattern can't be compared in the comparison
+/// mode, e.g., isn't an integer in an integer search, or is NaN in a real search.
+/// A sorted search compares only the elements it examines.
+pub fn lsearch(list: &MoltValue, pattern: &MoltValue, options: &SearchOptions) -> MoltResult {
+    let list = list.as_list()?;
+    let start = options.start.min(list.len());
+
+    // FIRST, find the indices of the matching elements.
+    let found = if options.match_mode == MatchMode::Sorted && !options.negate {
+        sorted_search(&list, start, pattern, options)?
+    } else {
+        linear_search(&list, start, pattern, options)?
+    };
+
+    // NEXT, return them as the options require.
+    if options.all {
+        let out: MoltList = if options.inline {
+            found.iter().map(|&i| list[i].clone()).collect()
+        } else {
+            found.iter().map(|&i| index_value(i)).collect()
+        };
+
+        Ok(MoltValue::from_list(out))
+    } else if options.inline {
+        Ok(match found.first() {
+            Some(&i) => list[i].clone(),
+            None => MoltValue::from_string(String::new()),
+        })
+    } else {
+        Ok(MoltValue::from_int(
+            found.first().map_or(-1, |&i| i as MoltInt),
+        ))
+    }
+}
+
+// Finds the indices of the matching elements at or after start, one at a time; or
+// only the first, if the options don't call for all of them.
+fn linear_search(
+    list: &[MoltValue],
+    start: usize,
+    pattern: &MoltValue,
+    options: &SearchOptions,
+) -> Result<Vec<usize>, ResultCode> {
+    let mut found = Vec::new();
+
+    for (i, elem) in list.iter().enumerate().skip(start) {
+        if options.matches(elem, pattern)? {
+            found.push(i);
+
+            if !options.all {
+                break;
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+// Finds the indices of the matching elements at or after start by binary search,
+// given that the list is sorted; or only the first, if the options don't call for
+// all of them.
+fn sorted_search(
+    list: &[MoltValue],
+    start: usize,
+    pattern: &MoltValue,
+    options: &SearchOptions,
+) -> Result<Vec<usize>, ResultCode> {
+    // FIRST, find the first element that doesn't sort before the pattern.
+    let (mut lo, mut hi) = (start, list.len());
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+
+        if options.compare(&list[mid], pattern)? == Ordering::Less {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    // NEXT, the matches are the elements from there on that are equal to the pattern.
+    let mut found = Vec::new();
+
+    for (i, elem) in list.iter().enumerate().skip(lo) {
+        if options.compare(elem, pattern)? != Ordering::Equal {
+            break;
+        }
+
+        found.push(i);
+
+        if !options.all {
+            break;
+        }
+    }
+
+    Ok(found)
+}
+
+// Returns a list index as a value.
+fn index_value(i: usize) -> MoltValue {
+    MoltValue::from_int(i as MoltInt)
+}
+
+/// Matches a string against a glob pattern, as TCL's `string match` does.
+///
+/// In the pattern, `*` matches any sequence of characters, including none; `?`
+/// matches any single character; `[chars]` matches any single character in the set,
+/// which may include ranges like `a-z`; and `\x` matches the character `x`
+/// literally.  All other characters match themselves, ignoring case if `nocase` is
+/// true.
+pub fn glob_match(pattern: &str, str: &str, nocase: bool) -> bool {
+    let (mut p, mut s) = (pattern, str);
+
+    // Where to resume if a match fails after a star: the pattern following the
+    // star, and the string from which the star matched nothing.
+    let mut star: Option<(&str, &str)> = None;
+
+    loop {
+        // FIRST, handle stars and the end of the pattern.
+        if p.starts_with('*') {
+            p = p.trim_start_matches('*');
+
+            if p.is_empty() {
+                return true;
+            }
+
+            star = Some((p, s));
+            continue;
+        }
+
+        if p.is_empty() && s.is_empty() {
+            return true;
+        }
+
+        // NEXT, match the next character of the string.
+        let step = s
+            .chars()
+            .next()
+            .and_then(|c| match_token(p, c, nocase).map(|p_rest| (p_rest, &s[c.len_utf8()..])));
+
+        match (step, star) {
+            (Some((p_rest, s_rest)), _) => {
+                p = p_rest;
+                s = s_rest;
+            }
+
+            // On failure, let the last star match one more character, and retry.
+            (None, Some((star_p, star_s))) => {
+                let mut chars = star_s.chars();
+
+                if chars.next().is_none() {
+                    return false;
+                }
+
+                star = Some((star_p, chars.as_str()));
+                p = star_p;
+                s = chars.as_str();
+            }
+
+            (None, None) => return false,
+        }
+    }
+}
+
+// If the pattern, which doesn't begin with a star, begins with a token that matches
+// the character, returns the rest of the pattern.
+fn match_token(pattern: &str, c: char, nocase: bool) -> Option<&str> {
+    let mut chars = pattern.chars();
+
+    let lit = match chars.next()? {
+        '?' => return Some(chars.as_str()),
+        '[' => return match_set(chars.as_str(), c, nocase),
+        '\\' => chars.next()?,
+        lit => lit,
+    };
+
+    if fold(lit, nocase) == fold(c, nocase) {
+        Some(chars.as_str())
+    } else {
+        None
+    }
+}
+
+// If the character is in the set at the beginning of the pattern, which follows
+// its opening bracket, returns the rest of the pattern.  An unterminated set
+// matches nothing.
+fn match_set(pattern: &str, c: char, nocase: bool) -> Option<&str> {
+    let c = fold(c, nocase);
+    let mut chars = pattern.chars();
+    let mut matched = false;
+
+    loop {
+        let first = match chars.next()? {
+            ']' => break,
+            first => fold(first, nocase),
+        };
+
+        // A range, e.g., a-z, in either order.  As in TCL, the character after the
+        // hyphen always ends the range, even if it's the closing bracket.
+        if chars.as_str().starts_with('-') {
+            chars.next();
+            let last = fold(chars.next()?, nocase);
+            matched |= first.min(last) <= c && c <= first.max(last);
+        } else {
+            matched |= first == c;
+        }
+    }
+
+    if matched {
+        Some(chars.as_str())
+    } else {
+        None
+    }
+}
+
+// Folds the character to lowercase if the match ignores case.
+fn fold(c: char, nocase: bool) -> char {
+    if nocase {
+        compare::lower(c)
+    } else {
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::RepKind;
+
+    fn val(str: &str) -> MoltValue {
+        MoltValue::from_string(str.to_string())
+    }
+
+    // Searches the list string for the pattern string, returning the result as a
+    // string.
+    fn search(list: &str, pattern: &str, options: SearchOptions) -> Result<String, ResultCode> {
+        lsearch(&val(list), &val(pattern), &options).map(|v| v.to_string())
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("", "", false));
+        assert!(glob_match("*", "", false));
+        assert!(glob_match("a*c", "abbbc", false));
+        assert!(glob_match("a*b*c", "aXbYbZc", false));
+        assert!(!glob_match("a*c", "abcd", false));
+        assert!(glob_match("??", "éx", false));
+        assert!(!glob_match("?", "", false));
+        assert!(glob_match("[a-c]x", "bx", false));
+        assert!(glob_match("[c-a]x", "bx", false));
+        assert!(glob_match("[xyz]", "y", false));
+        assert!(!glob_match("[xyz]", "w", false));
+        assert!(!glob_match("[a-]", "-", false));
+        assert!(!glob_match("[a-]", "_", false));
+        assert!(glob_match("[a-]b]", "_", false));
+        assert!(!glob_match("[ab", "a", false));
+        assert!(glob_match("\\*", "*", false));
+        assert!(!glob_match("\\*", "x", false));
+        assert!(!glob_match("ABC", "abc", false));
+        assert!(glob_match("A[B-C]*", "abc", true));
+    }
+
+    #[test]
+    fn modes() {
+        let list = "apple banana cherry banana";
+        assert_eq!(search(list, "b*", SearchOptions::new()), Ok("1".into()));
+        assert_eq!(search(list, "z*", SearchOptions::new()), Ok("-1".into()));
+        assert_eq!(
+            search(list, "b*", SearchOptions::new().exact()),
+            Ok("-1".into())
+        );
+        assert_eq!(
+            search(list, "cherry", SearchOptions::new().exact()),
+            Ok("2".into())
+        );
+        assert_eq!(
+            search(
+                list,
+                "CHERRY",
+                SearchOptions::new().mode(CompareMode::Nocase)
+            ),
+            Ok("2".into())
+        );
+        assert_eq!(
+            search(
+                "1 01 0x1 2",
+                "1",
+                SearchOptions::new().exact().mode(CompareMode::Integer)
+            ),
+            Ok("0".into())
+        );
+        assert_eq!(
+            search(
+                "1 01 0x1 2",
+                "1",
+                SearchOptions::new()
+                    .exact()
+                    .mode(CompareMode::Integer)
+                    .all()
+            ),
+            Ok("0 1 2".into())
+        );
+        assert_eq!(
+            search(
+                "1 x",
+                "2",
+                SearchOptions::new().exact().mode(CompareMode::Integer)
+            ),
+            molt_err!("expected integer but got \"x\"")
+        );
+        assert_eq!(
+            search("a {b", "a", SearchOptions::new()),
+            molt_err!("unmatched open brace in list")
+        );
+    }
+
+    #[test]
+    fn all_inline_not_start() {
+        let list = "apple banana cherry banana";
+        assert_eq!(
+            search(list, "b*", SearchOptions::new().all()),
+            Ok("1 3".into())
+        );
+        assert_eq!(
+            search(list, "b*", SearchOptions::new().inline()),
+            Ok("banana".into())
+        );
+        assert_eq!(
+            search(list, "z*", SearchOptions::new().inline()),
+            Ok("".into())
+        );
+        assert_eq!(
+            search(list, "*e*", SearchOptions::new().all().inline()),
+            Ok("apple cherry".into())
+        );
+        assert_eq!(
+            search(list, "z*", SearchOptions::new().all()),
+            Ok("".into())
+        );
+        assert_eq!(
+            search(list, "b*", SearchOptions::new().negate()),
+            Ok("0".into())
+        );
+        assert_eq!(
+            search(list, "b*", SearchOptions::new().negate().all().inline()),
+            Ok("apple cherry".into())
+        );
+        assert_eq!(
+            search(list, "b*", SearchOptions::new().start(2)),
+            Ok("3".into())
+        );
+        assert_eq!(
+            search(list, "b*", SearchOptions::new().start(9)),
+            Ok("-1".into())
+        );
+    }
+
+    #[test]
+    fn sorted() {
+        let options = SearchOptions::new().sorted();
+        assert_eq!(search("a b b b c", "b", options.clone()), Ok("1".into()));
+        assert_eq!(
+            search("a b b b c", "b", options.clone().all()),
+            Ok("1 2 3".into())
+        );
+        assert_eq!(search("a b b b c", "bb", options.clone()), Ok("-1".into()));
+        assert_eq!(search("a b b b c", "z", options.clone()), Ok("-1".into()));
+        assert_eq!(
+            search("a b b b c", "b", options.clone().start(2)),
+            Ok("2".into())
+        );
+        assert_eq!(search("", "b", options.clone()), Ok("-1".into()));
+        assert_eq!(
+            search("a b b b c", "b", options.clone().negate().all()),
+            Ok("0 4".into())
+        );
+        assert_eq!(
+            search("c b a", "a", options.clone().decreasing()),
+            Ok("2".into())
+        );
+
+        let numeric = options.mode(CompareMode::Integer);
+        assert_eq!(
+            search("1 5 10 50 100", "10", numeric.clone()),
+            Ok("2".into())
+        );
+        assert_eq!(
+            search("1 5 10 50 100", "0xa", numeric.clone()),
+            Ok("2".into())
+        );
+        assert_eq!(
+            search(
+                "x2 x10 x100",
+                "x10",
+                SearchOptions::new().sorted().mode(CompareMode::Dictionary)
+            ),
+            Ok("1".into())
+        );
+
+        // NaN can't be ordered, so a real search that compares it fails.
+        let real = SearchOptions::new().sorted().mode(CompareMode::Real);
+        assert_eq!(
+            search("0 1 NaN 2 3", "2", real.clone()),
+            molt_err!("floating point value is Not a Number")
+        );
+        assert_eq!(
+            search("0 1 2 3", "NaN", real.clone()),
+            molt_err!("floating point value is Not a Number")
+        );
+        assert_eq!(
+            search("0 NaN 2", "2", real.exact()),
+            molt_err!("floating point value is Not a Number")
+        );
+    }
+
+    #[test]
+    fn index() {
+        let list = "{a 1} {b 2} {c 1}";
+        assert_eq!(
+            search(list, "1", SearchOptions::new().index(&[1]).all()),
+            Ok("0 2".into())
+        );
+        assert_eq!(
+            search(list, "2", SearchOptions::new().index(&[1]).inline()),
+            Ok("b 2".into())
+        );
+        assert_eq!(
+            search(list, "b", SearchOptions::new().index(&[0]).sorted()),
+            Ok("1".into())
+        );
+        assert_eq!(
+            search("{a 1} b", "1", SearchOptions::new().index(&[1]).all()),
+            molt_err!("element 1 missing from sublist \"b\"")
+        );
+    }
+
+    #[test]
+    fn reuses_list_rep() {
+        let sublist = MoltValue::from_string("x 1".to_string());
+        let list = MoltValue::from_list(vec![val("a 0"), sublist]);
+        let options = SearchOptions::new().index(&[1]).exact().inline();
+
+        let found = lsearch(&list, &val("1"), &options).unwrap();
+        assert!(!list.has_string_rep());
+        assert_eq!(found.as_str(), "x 1");
+
+        // The nested list was parsed in place.
+        assert_eq!(list.as_list().unwrap()[1].rep_kind(), RepKind::List);
+    }
+}
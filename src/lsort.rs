@@ -0,0 +1,448 @@
+//! List Sorting
+//!
+//! This module implements TCL's `lsort`: sorting a list value's elements, or groups
+//! of elements, by any of the comparison modes defined in the `compare` module, or
+//! by a custom comparison function.  See [`lsort`] and [`SortOptions`].
+//!
+//! As in TCL, the sort is a stable merge sort: elements that compare equal retain
+//! their relative order, whether the sort is increasing or decreasing.  Comparisons
+//! may fail, e.g., when an element isn't an integer in an integer sort; the first
+//! failure ends the sort, and is returned as the result.
+//!
+//! [`lsort`]: fn.lsort.html
+//! [`SortOptions`]: struct.SortOptions.html
+
+use crate::compare::CompareMode;
+use crate::value::MoltList;
+use crate::value::MoltResult;
+use crate::value::MoltValue;
+use crate::value::ResultCode;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// A custom comparison function for `lsort`.  It should return an integer value
+/// that is negative, zero, or positive as its first argument sorts before, with, or
+/// after its second.
+pub type SortCommand = Rc<dyn Fn(&MoltValue, &MoltValue) -> MoltResult>;
+
+/// The options for `lsort`.  The defaults sort the list's elements in increasing
+/// ASCII order, retaining duplicates.
+///
+/// # Example
+///
+/// ```
+/// use value::compare::CompareMode;
+/// use value::lsort::lsort;
+/// use value::lsort::SortOptions;
+/// use value::value::MoltValue;
+///
+/// let list = MoltValue::from_string("carrot 10 apple 50 banana 25".to_string());
+/// let options = SortOptions::new()
+///     .stride(2)
+///     .index(&[1])
+///     .mode(CompareMode::Integer)
+///     .decreasing();
+/// let sorted = lsort(&list, &options).unwrap();
+/// assert_eq!(sorted.as_str(), "apple 50 banana 25 carrot 10");
+/// ```
+#[derive(Clone, Default)]
+pub struct SortOptions {
+    decreasing: bool,
+    mode: CompareMode,
+    index: Vec<usize>,
+    stride: Option<usize>,
+    unique: bool,
+    command: Option<SortCommand>,
+}
+
+impl SortOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sorts in increasing order.  This is the default.
+    pub fn increasing(mut self) -> Self {
+        self.decreasing = false;
+        self
+    }
+
+    /// Sorts in decreasing order.
+    pub fn decreasing(mut self) -> Self {
+        self.decreasing = true;
+        self
+    }
+
+    /// Sets the comparison mode.  The default is `CompareMode::Ascii`.
+    pub fn mode(mut self, mode: CompareMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sorts the elements by the given elements of each, treating each as a list.
+    /// The path indexes into nested lists; e.g., `&[1, 0]` sorts by the first
+    /// element of each element's second element.  It's an error if an element has
+    /// no such element.
+    ///
+    /// With `stride`, the first index in the path is the index of the element
+    /// within each group.
+    pub fn index(mut self, path: &[usize]) -> Self {
+        self.index = path.to_vec();
+        self
+    }
+
+    /// Sorts the list in groups of `stride` elements, which are kept together.  The
+    /// groups are sorted by their first elements, or by the elements given by
+    /// `index`.  The stride must be at least 2, and the length of the list must be a
+    /// multiple of it.
+    pub fn stride(mut self, stride: usize) -> Self {
+        self.stride = Some(stride);
+        self
+    }
+
+    /// Retains only the last of each set of elements that compare equal.
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    /// Compares the elements using the given function instead of the comparison
+    /// mode.  See `SortCommand`.  It's an error if the function returns anything
+    /// but an integer.
+    pub fn command<F>(mut self, command: F) -> Self
+    where
+        F: Fn(&MoltValue, &MoltValue) -> MoltResult + 'static,
+    {
+        self.command = Some(Rc::new(command));
+        self
+    }
+
+    // Compares two sort keys as the options require.
+    fn compare(&self, a: &MoltValue, b: &MoltValue) -> Result<Ordering, ResultCode> {
+        let order = match &self.command {
+            Some(command) => match command(a, b)?.as_int() {
+                Ok(result) => result.cmp(&0),
+                Err(_) => return molt_err!("-compare command returned non-integer result"),
+            },
+            None => a.compare(b, self.mode)?,
+        };
+
+        Ok(if self.decreasing {
+            order.reverse()
+        } else {
+            order
+        })
+    }
+}
+
+/// Sorts the list value's elements as the options require, returning the sorted
+/// list as a new value.  It's an error if the value isn't a list, if the options
+/// are inconsistent with the list, or if any comparison fails.
+///
+/// The list's elements are retrieved from its list data rep, so a list that has
+/// already been parsed isn't parsed again.
+pub fn lsort(list: &MoltValue, options: &SortOptions) -> MoltResult {
+    let list = list.as_list()?;
+    let stride = options.stride.unwrap_or(1);
+
+    // FIRST, validate the stride.
+    if options.stride.is_some() {
+        if stride < 2 {
+            return molt_err!("stride length must be at least 2");
+        }
+
+        if list.len() % stride != 0 {
+            return molt_err!("list size must be a multiple of the stride length");
+        }
+
+        if options.index.first().is_some_and(|&i| i >= stride) {
+            return molt_err!(
+                "when used with \"-stride\", the leading \"-index\" value must be within the group"
+            );
+        }
+    }
+
+    // NEXT, get the sort key for each group: its first element, or the indexed
+    // element.
+    let mut keys = Vec::with_capacity(list.len() / stride);
+
+    for start in (0..list.len()).step_by(stride) {
+        let key = match options.stride {
+            Some(_) => sort_key(&list[start..start + stride], &options.index)?,
+            None => sort_key(&list[start..=start], &options.index)?,
+        };
+        keys.push((key, start));
+    }
+
+    // NEXT, sort the keys, keeping only the last of each set of equal keys if need be.
+    let mut keys = merge_sort(keys, &mut |(a, _), (b, _)| options.compare(a, b))?;
+
+    if options.unique {
+        keys = last_of_each(keys, &mut |(a, _), (b, _)| options.compare(a, b))?;
+    }
+
+    // NEXT, build the new list.
+    let mut out = MoltList::with_capacity(keys.len() * stride);
+
+    for (_, start) in keys {
+        out.extend_from_slice(&list[start..start + stride]);
+    }
+
+    Ok(MoltValue::from_list(out))
+}
+
+// Returns the sort key for a group of elements: the element at the index path
+// within the group, where the group and any nested elements are lists.  If the group
+// is a single element, the path indexes into that element.
+fn sort_key(group: &[MoltValue], path: &[usize]) -> MoltResult {
+    match (group, path) {
+        ([elem], _) => nested_element(elem, path),
+        (_, []) => Ok(group[0].clone()),
+        (_, [first, rest @ ..]) => nested_element(&group[*first], rest),
+    }
+}
+
+// Returns the element at the index path within the value, where the value and any
+// nested elements are lists.  The nested lists are parsed in place, so that their
+// list data reps are cached.
+pub(crate) fn nested_element(value: &MoltValue, path: &[usize]) -> MoltResult {
+    match path {
+        [] => Ok(value.clone()),
+        [i, rest @ ..] => match value.as_list()?.get(*i) {
+            Some(elem) => nested_element(elem, rest),
+            None => molt_err!("element {} missing from sublist \"{}\"", i, value),
+        },
+    }
+}
+
+// Sorts the items using the comparison function, a stable merge sort that stops at
+// the first failed comparison.
+fn merge_sort<T, F>(mut items: Vec<T>, cmp: &mut F) -> Result<Vec<T>, ResultCode>
+where
+    F: FnMut(&T, &T) -> Result<Ordering, ResultCode>,
+{
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(items, cmp)?;
+    let right = merge_sort(right, cmp)?;
+
+    // Merge the sorted halves, taking from the left half unless the right half's
+    // item is strictly less, so that equal items retain their order.
+    let mut out = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        if cmp(r, l)? == Ordering::Less {
+            out.extend(right.next());
+        } else {
+            out.extend(left.next());
+        }
+    }
+
+    out.extend(left);
+    out.extend(right);
+    Ok(out)
+}
+
+// Given sorted items, retains only the last of each run of items that compare equal.
+fn last_of_each<T, F>(items: Vec<T>, cmp: &mut F) -> Result<Vec<T>, ResultCode>
+where
+    F: FnMut(&T, &T) -> Result<Ordering, ResultCode>,
+{
+    let mut out: Vec<T> = Vec::with_capacity(items.len());
+
+    for item in items {
+        if let Some(last) = out.last_mut() {
+            if cmp(last, &item)? == Ordering::Equal {
+                *last = item;
+                continue;
+            }
+        }
+
+        out.push(item);
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn val(str: &str) -> MoltValue {
+        MoltValue::from_string(str.to_string())
+    }
+
+    // Sorts the list string with the options, returning the result as a string.
+    fn sort(list: &str, options: SortOptions) -> Result<String, ResultCode> {
+        lsort(&val(list), &options).map(|v| v.to_string())
+    }
+
+    #[test]
+    fn modes() {
+        assert_eq!(sort("", SortOptions::new()), Ok("".into()));
+        assert_eq!(sort("b a C c", SortOptions::new()), Ok("C a b c".into()));
+        assert_eq!(
+            sort("b a C c", SortOptions::new().decreasing()),
+            Ok("c b a C".into())
+        );
+        assert_eq!(
+            sort("b A a C c", SortOptions::new().mode(CompareMode::Nocase)),
+            Ok("A a b C c".into())
+        );
+        assert_eq!(
+            sort(
+                "x10 x9 X9",
+                SortOptions::new().mode(CompareMode::Dictionary)
+            ),
+            Ok("X9 x9 x10".into())
+        );
+        assert_eq!(
+            sort(
+                "10 9 0x10 -1",
+                SortOptions::new().mode(CompareMode::Integer)
+            ),
+            Ok("-1 9 10 0x10".into())
+        );
+        assert_eq!(
+            sort("1.5 1 1e-1", SortOptions::new().mode(CompareMode::Real)),
+            Ok("1e-1 1 1.5".into())
+        );
+
+        // NaN can't be ordered, so a real sort that includes it fails.
+        assert_eq!(
+            sort(
+                "3 NaN 1 2 NaN 0",
+                SortOptions::new().mode(CompareMode::Real)
+            ),
+            molt_err!("floating point value is Not a Number")
+        );
+
+        assert_eq!(
+            sort("1 x 2", SortOptions::new().mode(CompareMode::Integer)),
+            molt_err!("expected integer but got \"x\"")
+        );
+        assert_eq!(
+            sort("a {b", SortOptions::new()),
+            molt_err!("unmatched open brace in list")
+        );
+    }
+
+    #[test]
+    fn stable() {
+        let options = SortOptions::new().index(&[1]).mode(CompareMode::Integer);
+        assert_eq!(
+            sort("{a 2} {b 1} {c 2} {d 1}", options.clone()),
+            Ok("{b 1} {d 1} {a 2} {c 2}".into())
+        );
+        assert_eq!(
+            sort("{a 2} {b 1} {c 2} {d 1}", options.decreasing()),
+            Ok("{a 2} {c 2} {b 1} {d 1}".into())
+        );
+    }
+
+    #[test]
+    fn index() {
+        assert_eq!(
+            sort("{a {x 3}} {b {y 1}}", SortOptions::new().index(&[1, 1])),
+            Ok("{b {y 1}} {a {x 3}}".into())
+        );
+        assert_eq!(
+            sort("{a 1} {b}", SortOptions::new().index(&[1])),
+            molt_err!("element 1 missing from sublist \"b\"")
+        );
+    }
+
+    #[test]
+    fn stride() {
+        let list = "carrot 10 apple 50 banana 25";
+        assert_eq!(
+            sort(list, SortOptions::new().stride(2)),
+            Ok("apple 50 banana 25 carrot 10".into())
+        );
+        assert_eq!(
+            sort(list, SortOptions::new().stride(2).index(&[1])),
+            Ok("carrot 10 banana 25 apple 50".into())
+        );
+        assert_eq!(
+            sort(
+                "{a 2} x {b 1} y",
+                SortOptions::new().stride(2).index(&[0, 1])
+            ),
+            Ok("{b 1} y {a 2} x".into())
+        );
+
+        assert_eq!(
+            sort(list, SortOptions::new().stride(1)),
+            molt_err!("stride length must be at least 2")
+        );
+        assert_eq!(
+            sort(list, SortOptions::new().stride(4)),
+            molt_err!("list size must be a multiple of the stride length")
+        );
+        assert_eq!(
+            sort(list, SortOptions::new().stride(2).index(&[2])),
+            molt_err!(
+                "when used with \"-stride\", the leading \"-index\" value must be within the group"
+            )
+        );
+    }
+
+    #[test]
+    fn unique() {
+        assert_eq!(
+            sort("c a b a c", SortOptions::new().unique()),
+            Ok("a b c".into())
+        );
+        assert_eq!(
+            sort("{a 1} {c 2} {a 3}", SortOptions::new().index(&[0]).unique()),
+            Ok("{a 3} {c 2}".into())
+        );
+        assert_eq!(
+            sort(
+                "B a b A",
+                SortOptions::new().mode(CompareMode::Nocase).unique()
+            ),
+            Ok("A b".into())
+        );
+    }
+
+    #[test]
+    fn command() {
+        // Sort by length.
+        let by_length = SortOptions::new().command(|a, b| {
+            let diff = a.as_str().len() as i64 - b.as_str().len() as i64;
+            Ok(MoltValue::from_int(diff))
+        });
+        assert_eq!(
+            sort("ccc a bb dd", by_length.clone()),
+            Ok("a bb dd ccc".into())
+        );
+        assert_eq!(
+            sort("ccc a bb dd", by_length.decreasing()),
+            Ok("ccc bb dd a".into())
+        );
+
+        let bad = SortOptions::new().command(|_, _| Ok(val("nope")));
+        assert_eq!(
+            sort("a b", bad),
+            molt_err!("-compare command returned non-integer result")
+        );
+
+        let failing = SortOptions::new().command(|_, _| molt_err!("oops"));
+        assert_eq!(sort("a b", failing.clone()), molt_err!("oops"));
+        assert_eq!(sort("a", failing), Ok("a".into()));
+    }
+
+    #[test]
+    fn reuses_list_rep() {
+        let list = MoltValue::from_list(vec![MoltValue::from_int(3), MoltValue::from_int(1)]);
+        let sorted = lsort(&list, &SortOptions::new().mode(CompareMode::Integer)).unwrap();
+        assert!(!list.has_string_rep());
+        assert_eq!(sorted.as_str(), "1 3");
+    }
+}
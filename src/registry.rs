@@ -0,0 +1,247 @@
+//! The Type Registry
+//!
+//! `MoltValue::as_other` can only convert a value to an external type that the
+//! caller knows at compile time.  The [`TypeRegistry`] allows external types to be
+//! registered by name, so that tools can convert values to types named at runtime,
+//! list the known types, and reconstruct typed values from serialized data.
+//!
+//! Serialized values are _tagged_: a tagged value is a two-element list whose
+//! elements are the value's type name and its string rep, e.g., `{rgb #ff0000}`.
+//!
+//! [`TypeRegistry`]: struct.TypeRegistry.html
+
+use crate::value::MoltResult;
+use crate::value::MoltType;
+use crate::value::MoltValue;
+use crate::value::ResultCode;
+use std::any::TypeId;
+use std::collections::BTreeMap;
+
+/// A function that converts a value's data rep to a particular external type.
+type Converter = fn(&MoltValue) -> Result<(), ResultCode>;
+
+/// A registry of external types, keyed by type name.
+#[derive(Clone, Debug, Default)]
+pub struct TypeRegistry {
+    // The registered types, by name: each type's ID and converter.
+    types: BTreeMap<&'static str, (TypeId, Converter)>,
+}
+
+impl TypeRegistry {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers external type `T` under its `MoltType::type_name`.  Registering
+    /// a type a second time has no effect.  It's an error if a different type has
+    /// already been registered with the same name.
+    pub fn register<T: MoltType>(&mut self) -> Result<(), ResultCode> {
+        let type_name = T::type_name();
+
+        match self.types.get(type_name) {
+            Some((type_id, _)) if *type_id != TypeId::of::<T>() => {
+                molt_err!("type name \"{}\" is already registered", type_name)
+            }
+            _ => {
+                self.types
+                    .insert(type_name, (TypeId::of::<T>(), convert::<T>));
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns true if a type with the given name has been registered.
+    pub fn is_registered(&self, type_name: &str) -> bool {
+        self.types.contains_key(type_name)
+    }
+
+    /// Returns the names of the registered types, in sorted order.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.types.keys().cloned().collect()
+    }
+
+    /// Converts the value to the named type, caching the result as the value's
+    /// data rep, and returns the value.  It's an error if the type is unknown or if
+    /// the value can't be converted.
+    pub fn convert(&self, value: &MoltValue, type_name: &str) -> MoltResult {
+        let converter = self.converter(type_name)?;
+        converter(value)?;
+        Ok(value.clone())
+    }
+
+    /// Returns the value as a tagged value: a list of its external type's name and its
+    /// string rep.  It's an error if the value doesn't currently have the data rep of
+    /// a registered type.
+    pub fn to_tagged(&self, value: &MoltValue) -> MoltResult {
+        match value.other_type_name() {
+            Some(type_name) if self.is_registered(type_name) => Ok(MoltValue::from_list(vec![
+                MoltValue::from_string(type_name.to_string()),
+                MoltValue::from_string(value.to_string()),
+            ])),
+            Some(type_name) => molt_err!("unknown type \"{}\"", type_name),
+            None => molt_err!("value has no external type: \"{}\"", value),
+        }
+    }
+
+    /// Reconstructs a typed value from a tagged value, as produced by `to_tagged`.
+    /// The result has the named type as its data rep.
+    pub fn from_tagged(&self, tagged: &MoltValue) -> MoltResult {
+        let list = tagged.as_list()?;
+
+        if list.len() != 2 {
+            return molt_err!(
+                "expected tagged value {{type value}} but got \"{}\"",
+                tagged
+            );
+        }
+
+        let value = MoltValue::from_string(list[1].to_string());
+        self.convert(&value, list[0].as_str())
+    }
+
+    /// Looks up the converter for the named type.
+    fn converter(&self, type_name: &str) -> Result<Converter, ResultCode> {
+        match self.types.get(type_name) {
+            Some((_, converter)) => Ok(*converter),
+            None => molt_err!("unknown type \"{}\"", type_name),
+        }
+    }
+}
+
+/// Converts the value's data rep to type `T`.
+fn convert<T: MoltType>(value: &MoltValue) -> Result<(), ResultCode> {
+    value.as_other::<T>().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flavor::Flavor;
+    use crate::rgb::RGB;
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::new();
+        registry.register::<RGB>().unwrap();
+        registry.register::<Flavor>().unwrap();
+        registry
+    }
+
+    // A type that claims RGB's name.
+    #[derive(Debug)]
+    struct FakeRgb;
+
+    impl MoltType for FakeRgb {
+        type Err = String;
+
+        fn type_name() -> &'static str {
+            "rgb"
+        }
+
+        fn to_molt_string(&self) -> String {
+            "fake".to_string()
+        }
+
+        fn from_molt_str(_str: &str) -> Result<Self, Self::Err> {
+            Ok(FakeRgb)
+        }
+    }
+
+    #[test]
+    fn register() {
+        let registry = registry();
+        assert!(registry.is_registered("rgb"));
+        assert!(registry.is_registered("flavor"));
+        assert!(!registry.is_registered("hsv"));
+        assert_eq!(registry.names(), vec!["flavor", "rgb"]);
+        assert!(TypeRegistry::new().names().is_empty());
+    }
+
+    #[test]
+    fn register_twice() {
+        let mut registry = registry();
+
+        // Registering the same type again is harmless.
+        assert_eq!(registry.register::<RGB>(), Ok(()));
+        assert_eq!(registry.names(), vec!["flavor", "rgb"]);
+
+        // A different type can't take a registered name.
+        assert_eq!(
+            registry.register::<FakeRgb>(),
+            molt_err!("type name \"rgb\" is already registered")
+        );
+
+        let value = MoltValue::from_string("#ff0000".to_string());
+        registry.convert(&value, "rgb").unwrap();
+        assert_eq!(*value.as_other::<RGB>().unwrap(), RGB::new(255, 0, 0));
+    }
+
+    #[test]
+    fn convert() {
+        let registry = registry();
+
+        let value = MoltValue::from_string("#ff0000".to_string());
+        let result = registry.convert(&value, "rgb").unwrap();
+        assert_eq!(result.other_type_name(), Some("rgb"));
+        assert_eq!(value.other_type_name(), Some("rgb"));
+        assert_eq!(*value.as_other::<RGB>().unwrap(), RGB::new(255, 0, 0));
+
+        let value = MoltValue::from_string("salty".to_string());
+        assert!(registry.convert(&value, "flavor").is_ok());
+        assert_eq!(*value.as_other::<Flavor>().unwrap(), Flavor::SALTY);
+
+        assert_eq!(
+            registry.convert(&value, "rgb").map(|_| ()),
+            molt_err!("expected rgb value but got \"salty\"")
+        );
+        assert_eq!(
+            registry.convert(&value, "hsv").map(|_| ()),
+            molt_err!("unknown type \"hsv\"")
+        );
+    }
+
+    #[test]
+    fn tagged() {
+        let registry = registry();
+
+        let value = MoltValue::from_other(RGB::new(1, 2, 3));
+        let tagged = registry.to_tagged(&value).unwrap();
+        assert_eq!(tagged.to_string(), "rgb #010203");
+
+        // Round trip through the string rep, as when serialized.
+        let tagged = MoltValue::from_string(tagged.to_string());
+        let value = registry.from_tagged(&tagged).unwrap();
+        assert_eq!(value.other_type_name(), Some("rgb"));
+        assert_eq!(*value.as_other::<RGB>().unwrap(), RGB::new(1, 2, 3));
+
+        // Errors
+        assert_eq!(
+            registry
+                .to_tagged(&MoltValue::from_string("abc".to_string()))
+                .map(|_| ()),
+            molt_err!("value has no external type: \"abc\"")
+        );
+        assert_eq!(
+            TypeRegistry::new().to_tagged(&value).map(|_| ()),
+            molt_err!("unknown type \"rgb\"")
+        );
+        assert_eq!(
+            registry
+                .from_tagged(&MoltValue::from_string("rgb".to_string()))
+                .map(|_| ()),
+            molt_err!("expected tagged value {{type value}} but got \"rgb\"")
+        );
+        assert_eq!(
+            registry
+                .from_tagged(&MoltValue::from_string("hsv {0 0 0}".to_string()))
+                .map(|_| ()),
+            molt_err!("unknown type \"hsv\"")
+        );
+        assert_eq!(
+            registry
+                .from_tagged(&MoltValue::from_string("flavor bitter".to_string()))
+                .map(|_| ()),
+            molt_err!("expected flavor but got \"bitter\"")
+        );
+    }
+}
@@ -2,7 +2,9 @@
 // https://rust-lang-nursery.github.io/rust-cookbook/text/string_parsing.html, as
 // a convenient example of FromStr, and then extended to provide fmt::Display as well.
 // (with tests to make sure I didn't screw it up.)
+use crate::value::MoltType;
 use crate::value::MoltValue;
+use crate::value::ResultCode;
 use std::fmt;
 use std::rc::Rc;
 use std::str::FromStr;
@@ -19,13 +21,29 @@ impl RGB {
         RGB { r, g, b }
     }
 
-    // TODO: The error should be a Molt ResultCode.
-    pub fn from_molt(value: &MoltValue) -> Result<Rc<Self>, String> {
-        if let Some(rgb) = value.as_other::<RGB>() {
-            Ok(rgb)
-        } else {
-            Err("Not a hex RGB string".to_string())
-        }
+    pub fn from_molt(value: &MoltValue) -> Result<Rc<Self>, ResultCode> {
+        value.as_other::<RGB>()
+    }
+}
+
+impl MoltType for RGB {
+    type Err = String;
+
+    fn type_name() -> &'static str {
+        "rgb"
+    }
+
+    fn to_molt_string(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+        str.parse()
+            .map_err(|_| format!("expected rgb value but got \"{}\"", str))
+    }
+
+    fn hash_value(&self) -> Option<u64> {
+        Some(((self.r as u64) << 16) | ((self.g as u64) << 8) | self.b as u64)
     }
 }
 
@@ -35,17 +53,13 @@ impl FromStr for RGB {
     // Parses a color hex code of the form '#rRgGbB..' into an
     // instance of 'RGB'.  The parsing is sketchy.
     fn from_str(hex_code: &str) -> Result<Self, Self::Err> {
-        if hex_code.len() == 7 {
+        if hex_code.len() == 7 && hex_code.is_ascii() && hex_code.starts_with('#') {
             let r = u8::from_str_radix(&hex_code[1..3], 16);
             let g = u8::from_str_radix(&hex_code[3..5], 16);
             let b = u8::from_str_radix(&hex_code[5..7], 16);
 
-            if r.is_ok() || g.is_ok() || b.is_ok() {
-                return Ok(RGB {
-                    r: r.unwrap(),
-                    g: g.unwrap(),
-                    b: b.unwrap(),
-                });
+            if let (Ok(r), Ok(g), Ok(b)) = (r, g, b) {
+                return Ok(RGB { r, g, b });
             }
         }
 
@@ -81,6 +95,14 @@ mod tests {
             RGB::from_str("010203"),
             Err("Not a hex RGB string".to_string())
         );
+        assert_eq!(
+            RGB::from_str("#01020g"),
+            Err("Not a hex RGB string".to_string())
+        );
+        assert_eq!(
+            RGB::from_str("#0102é"),
+            Err("Not a hex RGB string".to_string())
+        );
     }
 
     #[test]
@@ -91,5 +113,15 @@ mod tests {
         let rgb2 = RGB::from_molt(&value);
 
         assert_eq!(*(rgb2.unwrap()), RGB::new(255, 255, 255));
+
+        let value = MoltValue::from_string("#010203".to_string());
+        assert_eq!(*RGB::from_molt(&value).unwrap(), RGB::new(1, 2, 3));
+        assert_eq!(value.hash_other(), Some(0x010203));
+
+        let value = MoltValue::from_string("red".to_string());
+        assert_eq!(
+            RGB::from_molt(&value),
+            molt_err!("expected rgb value but got \"red\"")
+        );
     }
 }
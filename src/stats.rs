@@ -0,0 +1,211 @@
+//! Shimmer Statistics
+//!
+//! When the `instrument` feature is enabled, `MoltValue` counts the work it does
+//! converting between string reps and data reps, so that code that shimmers, e.g., a
+//! hot loop that thrashes between integer and list data reps, can be found.  The
+//! counts are kept per thread, and per kind of data rep: "int", "float", "bool",
+//! "list", "dict", or an external type's `MoltType::type_name`.
+//!
+//! Use [`snapshot`] to retrieve the counts, and [`reset`] to clear them.  When the
+//! feature is disabled, nothing is counted, and [`snapshot`] always returns an
+//! empty map.
+//!
+//! [`snapshot`]: fn.snapshot.html
+//! [`reset`]: fn.reset.html
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "instrument")]
+use std::cell::RefCell;
+
+/// The counts for one kind of data rep.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// The number of string reps generated from data reps of this kind.
+    pub string_reps: u64,
+
+    /// The number of attempts to parse a string rep as this kind of data rep,
+    /// successful or not.
+    pub parses: u64,
+
+    /// The number of data reps of this kind produced by registered converters.
+    pub conversions: u64,
+
+    /// The number of requests for this kind of data rep that were satisfied by the
+    /// current data rep or a cached one.
+    pub cache_hits: u64,
+
+    /// The number of data reps of this kind replaced by some other data rep.
+    pub replacements: u64,
+}
+
+/// A snapshot of the counts, by kind of data rep.
+pub type Snapshot = BTreeMap<&'static str, Counts>;
+
+/// The events that are counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Event {
+    StringRep,
+    Parse,
+    Conversion,
+    CacheHit,
+    Replacement,
+}
+
+#[cfg(feature = "instrument")]
+thread_local! {
+    static COUNTS: RefCell<Snapshot> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Returns true if the `instrument` feature is enabled, i.e., if events are counted.
+pub fn is_enabled() -> bool {
+    cfg!(feature = "instrument")
+}
+
+/// Returns the current thread's counts.
+///
+/// Only the calling thread's counts are returned: shimmering done by other threads,
+/// e.g., worker threads using values sent to them as `SyncMoltValue`s, isn't
+/// included.  `SyncMoltValue` itself is never counted, on any thread.
+pub fn snapshot() -> Snapshot {
+    #[cfg(feature = "instrument")]
+    {
+        COUNTS.with(|counts| counts.borrow().clone())
+    }
+
+    #[cfg(not(feature = "instrument"))]
+    {
+        BTreeMap::new()
+    }
+}
+
+/// Clears the current thread's counts.
+///
+/// Other threads' counts are unaffected; each thread must reset its own.
+pub fn reset() {
+    #[cfg(feature = "instrument")]
+    COUNTS.with(|counts| counts.borrow_mut().clear());
+}
+
+/// Counts an event for the given kind of data rep.
+#[cfg(feature = "instrument")]
+pub(crate) fn count(kind: &'static str, event: Event) {
+    COUNTS.with(|counts| {
+        let mut counts = counts.borrow_mut();
+        let entry = counts.entry(kind).or_default();
+
+        match event {
+            Event::StringRep => entry.string_reps += 1,
+            Event::Parse => entry.parses += 1,
+            Event::Conversion => entry.conversions += 1,
+            Event::CacheHit => entry.cache_hits += 1,
+            Event::Replacement => entry.replacements += 1,
+        }
+    });
+}
+
+/// Counts an event for the given kind of data rep.  Without the `instrument`
+/// feature, does nothing.
+#[cfg(not(feature = "instrument"))]
+#[inline(always)]
+pub(crate) fn count(_kind: &'static str, _event: Event) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::MoltValue;
+
+    #[test]
+    #[cfg(feature = "instrument")]
+    fn counts() {
+        reset();
+
+        // Thrash between int and list.
+        let myval = MoltValue::from_string("5".to_string());
+        for _ in 0..3 {
+            myval.as_int().unwrap();
+            myval.as_list().unwrap();
+        }
+        myval.as_list().unwrap();
+
+        let counts = snapshot();
+        let int = counts["int"];
+        assert_eq!(int.parses, 3);
+        assert_eq!(int.replacements, 3);
+        assert_eq!(int.cache_hits, 0);
+
+        let list = counts["list"];
+        assert_eq!(list.parses, 3);
+        assert_eq!(list.replacements, 2);
+        assert_eq!(list.cache_hits, 1);
+
+        // String reps are counted by the kind they were generated from.
+        MoltValue::from_int(1).as_str();
+        assert_eq!(snapshot()["int"].string_reps, 1);
+
+        // External types are counted by name.
+        let myval = MoltValue::from_string("#000000".to_string());
+        myval.as_other::<crate::rgb::RGB>().unwrap();
+        myval.as_other::<crate::rgb::RGB>().unwrap();
+        assert_eq!(snapshot()["rgb"].parses, 1);
+        assert_eq!(snapshot()["rgb"].cache_hits, 1);
+
+        reset();
+        assert!(snapshot().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "instrument")]
+    fn conversions() {
+        use crate::value::MoltType;
+
+        // A type used only by this test, converted from ints.
+        #[derive(Debug)]
+        struct Even(i64);
+
+        impl MoltType for Even {
+            type Err = String;
+
+            fn type_name() -> &'static str {
+                "even"
+            }
+
+            fn to_molt_string(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+                match str.parse() {
+                    Ok(int) if int % 2 == 0 => Ok(Even(int)),
+                    _ => Err(format!("expected even but got \"{}\"", str)),
+                }
+            }
+        }
+
+        MoltValue::register_converter::<i64, Even>(|int| {
+            if int % 2 == 0 {
+                Some(Even(*int))
+            } else {
+                None
+            }
+        });
+        reset();
+
+        // Only conversions that produce a data rep are counted.
+        MoltValue::from_int(2).as_other::<Even>().unwrap();
+        assert!(MoltValue::from_int(3).as_other::<Even>().is_err());
+
+        let even = snapshot()["even"];
+        assert_eq!(even.conversions, 1);
+        assert_eq!(even.parses, 1);
+    }
+
+    #[test]
+    #[cfg(not(feature = "instrument"))]
+    fn disabled() {
+        assert!(!is_enabled());
+        MoltValue::from_string("5".to_string()).as_int().unwrap();
+        assert!(snapshot().is_empty());
+        reset();
+    }
+}
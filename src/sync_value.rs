@@ -0,0 +1,590 @@
+//! The SyncMoltValue Type
+//!
+//! The [`SyncMoltValue`] struct is a thread-safe variant of `MoltValue`.  A `MoltValue`
+//! is built on `Rc` and `RefCell`, and so is neither `Send` nor `Sync`; a
+//! `SyncMoltValue` is built on `Arc`, `OnceLock`, and `RwLock`, and so can be shared
+//! freely between threads, e.g., to share parsed configuration values with worker
+//! threads.
+//!
+//! Otherwise, it is just like a `MoltValue`: it has the same `from_*`/`as_*` API,
+//! the same string rep and data rep, and the same shimmering semantics.  If two
+//! threads ask for different data reps at the same time, each gets the value it asked
+//! for, and the last conversion is cached.
+//!
+//! A `SyncMoltValue` can contain any external type that implements `MoltType` and
+//! is also `Send` and `Sync`.  Converters registered with
+//! `MoltValue::register_converter` are not used, as they produce `Rc`-based data
+//! reps that can't be shared between threads; `as_other` always parses the string rep.
+//!
+//! Values can be converted between the two types using `From`.  Both reps are carried
+//! over as they are, without parsing or formatting, except for external data reps,
+//! which are carried over by their string reps.
+//!
+//! [`SyncMoltValue`]: struct.SyncMoltValue.html
+
+use crate::dict;
+use crate::dict::MoltDict;
+use crate::list;
+use crate::value::Datum;
+use crate::value::MoltAny;
+use crate::value::MoltFloat;
+use crate::value::MoltInt;
+use crate::value::MoltType;
+use crate::value::MoltValue;
+use crate::value::ResultCode;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+
+//-----------------------------------------------------------------------------
+// Public Data Types
+
+/// A list of `SyncMoltValue`s: the thread-safe equivalent of a `MoltList`.
+pub type SyncMoltList = Vec<SyncMoltValue>;
+
+/// A dictionary of `SyncMoltValue`s: the thread-safe equivalent of a `MoltDict`.
+pub type SyncMoltDict = MoltDict<SyncMoltValue>;
+
+/// The `SyncMoltValue` type.  See [the module level documentation](index.html) for more.
+pub struct SyncMoltValue {
+    string_rep: OnceLock<Arc<String>>,
+    data_rep: RwLock<SyncDatum>,
+}
+
+// Clones share the string rep; the data rep is duplicated as its type requires.
+impl Clone for SyncMoltValue {
+    fn clone(&self) -> Self {
+        SyncMoltValue {
+            string_rep: self.string_rep.clone(),
+            data_rep: RwLock::new(self.data().clone()),
+        }
+    }
+}
+
+impl Debug for SyncMoltValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SyncMoltValue")
+            .field("string_rep", &self.string_rep.get())
+            .field("data_rep", &self.data())
+            .finish()
+    }
+}
+
+impl AsRef<str> for SyncMoltValue {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for SyncMoltValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SyncMoltValue {
+    /// Creates a new `SyncMoltValue` from the given string.
+    pub fn from_string(str: String) -> SyncMoltValue {
+        SyncMoltValue::from_reps(Some(str), SyncDatum::None)
+    }
+
+    /// Returns the value's string representation as a `&str`, computing it from the
+    /// data rep if necessary.  See `MoltValue::as_str`.
+    pub fn as_str(&self) -> &str {
+        // FIRST, if there's already a string, return it.
+        if let Some(str) = self.string_rep.get() {
+            return str;
+        }
+
+        // NEXT, convert the data to a string and save it for next time.  If
+        // another thread got there first, it's the same string, and the first one wins.
+        let str = self.data().to_string();
+        let _ = self.string_rep.set(Arc::new(str));
+        self.string_rep.get().unwrap()
+    }
+
+    /// Creates a new `SyncMoltValue` whose data representation is a `MoltInt`.
+    pub fn from_int(int: MoltInt) -> SyncMoltValue {
+        SyncMoltValue::from_reps(None, SyncDatum::Int(int))
+    }
+
+    /// Tries to return the `SyncMoltValue` as a `MoltInt`, parsing the
+    /// value's string representation if necessary.
+    pub fn as_int(&self) -> Result<MoltInt, ResultCode> {
+        // FIRST, if we have an integer then just return it.
+        if let SyncDatum::Int(int) = self.data() {
+            return Ok(int);
+        }
+
+        // NEXT, Try to parse the string_rep as an integer.
+        let int = MoltValue::get_int(self.as_str())?;
+        self.set_data(SyncDatum::Int(int));
+        Ok(int)
+    }
+
+    /// Creates a new `SyncMoltValue` whose data representation is a `MoltFloat`.
+    pub fn from_float(flt: MoltFloat) -> SyncMoltValue {
+        SyncMoltValue::from_reps(None, SyncDatum::Flt(flt))
+    }
+
+    /// Tries to return the `SyncMoltValue` as a `MoltFloat`, parsing the
+    /// value's string representation if necessary.
+    pub fn as_float(&self) -> Result<MoltFloat, ResultCode> {
+        // FIRST, if we have a float then just return it.
+        if let SyncDatum::Flt(flt) = self.data() {
+            return Ok(flt);
+        }
+
+        // NEXT, Try to parse the string_rep as a float.
+        let flt = MoltValue::get_float(self.as_str())?;
+        self.set_data(SyncDatum::Flt(flt));
+        Ok(flt)
+    }
+
+    /// Creates a new `SyncMoltValue` whose data representation is a `bool`.
+    pub fn from_bool(flag: bool) -> SyncMoltValue {
+        SyncMoltValue::from_reps(None, SyncDatum::Bool(flag))
+    }
+
+    /// Tries to return the `SyncMoltValue` as a `bool`, parsing the
+    /// value's string representation if necessary.
+    pub fn as_bool(&self) -> Result<bool, ResultCode> {
+        // FIRST, if we have a boolean then just return it.
+        if let SyncDatum::Bool(flag) = self.data() {
+            return Ok(flag);
+        }
+
+        // NEXT, Try to parse the string_rep as a boolean.
+        let flag = MoltValue::get_bool(self.as_str())?;
+        self.set_data(SyncDatum::Bool(flag));
+        Ok(flag)
+    }
+
+    /// Creates a new `SyncMoltValue` whose data representation is a `SyncMoltList`.
+    pub fn from_list(list: SyncMoltList) -> SyncMoltValue {
+        SyncMoltValue::from_reps(None, SyncDatum::List(Arc::new(list)))
+    }
+
+    /// Tries to return the `SyncMoltValue` as a `SyncMoltList`, parsing the
+    /// value's string representation if necessary.
+    pub fn as_list(&self) -> Result<Arc<SyncMoltList>, ResultCode> {
+        // FIRST, if we have a list then just return it.
+        if let SyncDatum::List(list) = self.data() {
+            return Ok(list);
+        }
+
+        // NEXT, parse the string_rep as a list.
+        let list: SyncMoltList = list::get_list(self.as_str())?
+            .iter()
+            .map(SyncMoltValue::from)
+            .collect();
+        let list = Arc::new(list);
+        self.set_data(SyncDatum::List(list.clone()));
+        Ok(list)
+    }
+
+    /// Creates a new `SyncMoltValue` whose data representation is a `SyncMoltDict`.
+    pub fn from_dict(dict: SyncMoltDict) -> SyncMoltValue {
+        SyncMoltValue::from_reps(None, SyncDatum::Dict(Arc::new(dict)))
+    }
+
+    /// Tries to return the `SyncMoltValue` as a `SyncMoltDict`, parsing the
+    /// value's string representation if necessary.
+    pub fn as_dict(&self) -> Result<Arc<SyncMoltDict>, ResultCode> {
+        // FIRST, if we have a dict then just return it.
+        if let SyncDatum::Dict(dict) = self.data() {
+            return Ok(dict);
+        }
+
+        // NEXT, parse the string_rep as a dict.
+        let dict: SyncMoltDict = dict::get_dict(self.as_str())?
+            .iter()
+            .map(|(key, value)| (SyncMoltValue::from(key), SyncMoltValue::from(value)))
+            .collect();
+        let dict = Arc::new(dict);
+        self.set_data(SyncDatum::Dict(dict.clone()));
+        Ok(dict)
+    }
+
+    /// Creates a new `SyncMoltValue` containing the given value of some external type.
+    ///
+    /// The type must implement `MoltType`, and must be `Send` and `Sync`.
+    pub fn from_other<T: MoltType + Send + Sync>(value: T) -> SyncMoltValue {
+        SyncMoltValue::from_reps(None, SyncDatum::Other(Arc::new(value)))
+    }
+
+    /// Tries to interpret the `SyncMoltValue` as a value of external type `T`,
+    /// parsing the value's string representation if necessary.  See
+    /// `MoltValue::as_other`; unlike it, registered converters aren't used.
+    pub fn as_other<T: MoltType + Send + Sync>(&self) -> Result<Arc<T>, ResultCode> {
+        // FIRST, if we have the desired type, return it.
+        if let SyncDatum::Other(other) = self.data() {
+            if let Some(out) = downcast::<T>(other) {
+                return Ok(out);
+            }
+        }
+
+        // NEXT, can we parse the string_rep as a T?  If so, save it back to
+        // the data_rep, and return it.
+        match T::from_molt_str(self.as_str()) {
+            Ok(tval) => {
+                let out = Arc::new(tval);
+                self.set_data(SyncDatum::Other(out.clone()));
+                Ok(out)
+            }
+            Err(err) => molt_err!("{}", err),
+        }
+    }
+
+    /// Tries to interpret the `SyncMoltValue` as a value of external type `T`,
+    /// returning a copy.
+    pub fn as_copy<T: MoltType + Send + Sync + Copy>(&self) -> Result<T, ResultCode> {
+        self.as_other::<T>().map(|out| *out)
+    }
+
+    /// Compares this value's external data rep with another value's.  See
+    /// `MoltValue::compare_other`.
+    pub fn compare_other(&self, other: &SyncMoltValue) -> Option<Ordering> {
+        match (&self.data(), &other.data()) {
+            (SyncDatum::Other(a), SyncDatum::Other(b)) => a.compare_any(b.as_any()),
+            _ => None,
+        }
+    }
+
+    /// Returns the hash of this value's external data rep.  See
+    /// `MoltValue::hash_other`.
+    pub fn hash_other(&self) -> Option<u64> {
+        match &self.data() {
+            SyncDatum::Other(other) => other.hash_any(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `MoltType::type_name` of this value's external data rep, or `None`
+    /// if the value doesn't currently have an external data rep.
+    pub fn other_type_name(&self) -> Option<&'static str> {
+        match &self.data() {
+            SyncDatum::Other(other) => Some(other.type_name()),
+            _ => None,
+        }
+    }
+
+    // Creates a value from its reps.
+    fn from_reps(string_rep: Option<String>, data: SyncDatum) -> SyncMoltValue {
+        let value = SyncMoltValue {
+            string_rep: OnceLock::new(),
+            data_rep: RwLock::new(data),
+        };
+
+        if let Some(str) = string_rep {
+            let _ = value.string_rep.set(Arc::new(str));
+        }
+
+        value
+    }
+
+    // Returns a shared copy of the data rep.  As with `MoltValue`, external types'
+    // methods are never called while the lock is held.  Since no code that can
+    // panic runs while the lock is held, the lock is never poisoned in practice.
+    fn data(&self) -> SyncDatum {
+        self.data_rep
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .share()
+    }
+
+    // Replaces the data rep.  The old data rep is dropped only after the lock
+    // is released.
+    fn set_data(&self, datum: SyncDatum) {
+        let mut data_ref = self
+            .data_rep
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let old = std::mem::replace(&mut *data_ref, datum);
+        drop(data_ref);
+        drop(old);
+    }
+}
+
+impl From<&MoltValue> for SyncMoltValue {
+    fn from(value: &MoltValue) -> Self {
+        let (string_rep, data) = value.reps();
+
+        let data = match data {
+            Datum::Int(int) => SyncDatum::Int(int),
+            Datum::Flt(flt) => SyncDatum::Flt(flt),
+            Datum::Bool(flag) => SyncDatum::Bool(flag),
+            Datum::List(list) => {
+                SyncDatum::List(Arc::new(list.iter().map(SyncMoltValue::from).collect()))
+            }
+            Datum::Dict(dict) => SyncDatum::Dict(Arc::new(
+                dict.iter()
+                    .map(|(key, value)| (SyncMoltValue::from(key), SyncMoltValue::from(value)))
+                    .collect(),
+            )),
+            Datum::Other(_) | Datum::None => SyncDatum::None,
+        };
+
+        // If there's no data rep, there must be a string rep.
+        let string_rep = match data {
+            SyncDatum::None => Some(value.as_str().to_string()),
+            _ => string_rep.map(|str| str.to_string()),
+        };
+
+        SyncMoltValue::from_reps(string_rep, data)
+    }
+}
+
+impl From<&SyncMoltValue> for MoltValue {
+    fn from(value: &SyncMoltValue) -> Self {
+        let data = match value.data() {
+            SyncDatum::Int(int) => Datum::Int(int),
+            SyncDatum::Flt(flt) => Datum::Flt(flt),
+            SyncDatum::Bool(flag) => Datum::Bool(flag),
+            SyncDatum::List(list) => {
+                Datum::List(std::rc::Rc::new(list.iter().map(MoltValue::from).collect()))
+            }
+            SyncDatum::Dict(dict) => Datum::Dict(std::rc::Rc::new(
+                dict.iter()
+                    .map(|(key, value)| (MoltValue::from(key), MoltValue::from(value)))
+                    .collect(),
+            )),
+            SyncDatum::Other(_) | SyncDatum::None => Datum::None,
+        };
+
+        // If there's no data rep, there must be a string rep.
+        let string_rep = match data {
+            Datum::None => Some(value.as_str().to_string()),
+            _ => value.string_rep.get().map(|str| str.to_string()),
+        };
+
+        MoltValue::from_reps(string_rep, data)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// The SyncMoltAny Trait: a MoltAny that can be shared between threads.
+
+trait SyncMoltAny: MoltAny + Send + Sync {
+    fn duplicate_sync(&self) -> Option<Arc<dyn SyncMoltAny>>;
+}
+
+impl<T: MoltType + Send + Sync> SyncMoltAny for T {
+    fn duplicate_sync(&self) -> Option<Arc<dyn SyncMoltAny>> {
+        self.duplicate()
+            .map(|dup| Arc::new(dup) as Arc<dyn SyncMoltAny>)
+    }
+}
+
+/// Downcast an `Arc<SyncMoltAny>` to an `Arc<T>`, if it is one.
+fn downcast<T: 'static>(other: Arc<dyn SyncMoltAny>) -> Option<Arc<T>> {
+    if other.as_any().is::<T>() {
+        unsafe { Some(Arc::from_raw(Arc::into_raw(other) as _)) }
+    } else {
+        None
+    }
+}
+
+//-----------------------------------------------------------------------------
+// SyncDatum enum: the data reps for SyncMoltValues, as for Datum.
+
+#[derive(Debug)]
+enum SyncDatum {
+    Int(MoltInt),
+    Flt(MoltFloat),
+    Bool(bool),
+    List(Arc<SyncMoltList>),
+    Dict(Arc<SyncMoltDict>),
+    Other(Arc<dyn SyncMoltAny>),
+    None,
+}
+
+impl SyncDatum {
+    // Returns a copy of the data rep that shares any reference-counted data.  Unlike
+    // `clone`, it never duplicates external data reps.
+    fn share(&self) -> SyncDatum {
+        match self {
+            SyncDatum::Int(int) => SyncDatum::Int(*int),
+            SyncDatum::Flt(flt) => SyncDatum::Flt(*flt),
+            SyncDatum::Bool(flag) => SyncDatum::Bool(*flag),
+            SyncDatum::List(list) => SyncDatum::List(list.clone()),
+            SyncDatum::Dict(dict) => SyncDatum::Dict(dict.clone()),
+            SyncDatum::Other(other) => SyncDatum::Other(other.clone()),
+            SyncDatum::None => SyncDatum::None,
+        }
+    }
+}
+
+// External data reps are duplicated as their type requires; all other data reps
+// are simply shared or copied.
+impl Clone for SyncDatum {
+    fn clone(&self) -> Self {
+        match self {
+            SyncDatum::Other(other) => {
+                SyncDatum::Other(other.duplicate_sync().unwrap_or_else(|| other.clone()))
+            }
+            _ => self.share(),
+        }
+    }
+}
+
+impl Display for SyncDatum {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SyncDatum::Int(int) => write!(f, "{}", int),
+            SyncDatum::Flt(flt) => write!(f, "{}", MoltValue::fmt_float(*flt)),
+            SyncDatum::Bool(flag) => write!(f, "{}", if *flag { 1 } else { 0 }),
+            SyncDatum::List(list) => write!(f, "{}", list::list_to_string(list)),
+            SyncDatum::Dict(dict) => write!(f, "{}", dict::dict_to_string(dict)),
+            SyncDatum::Other(other) => write!(f, "{}", other.to_molt_string()),
+            SyncDatum::None => write!(f, ""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rgb::RGB;
+    use std::thread;
+
+    fn val(str: &str) -> SyncMoltValue {
+        SyncMoltValue::from_string(str.to_string())
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<SyncMoltValue>();
+    }
+
+    #[test]
+    fn from_as() {
+        let myval = SyncMoltValue::from_int(5);
+        assert_eq!(myval.as_int(), Ok(5));
+        assert_eq!(myval.as_str(), "5");
+        assert_eq!(myval.as_float(), Ok(5.0));
+        assert_eq!(myval.to_string(), "5");
+
+        assert_eq!(SyncMoltValue::from_float(1.5).as_str(), "1.5");
+        assert_eq!(SyncMoltValue::from_bool(true).as_str(), "1");
+        assert_eq!(val("yes").as_bool(), Ok(true));
+        assert_eq!(
+            val("abc").as_int(),
+            molt_err!("expected integer but got \"abc\"")
+        );
+
+        let myval = SyncMoltValue::from_list(vec![val("a b"), SyncMoltValue::from_int(1)]);
+        assert_eq!(myval.as_str(), "{a b} 1");
+        let list = val("x {y z}").as_list().unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].as_str(), "y z");
+
+        let dict = val("a 1 b 2").as_dict().unwrap();
+        assert_eq!(dict.get("b").unwrap().as_int(), Ok(2));
+        assert_eq!(
+            SyncMoltValue::from_dict(dict.as_ref().clone()).as_str(),
+            "a 1 b 2"
+        );
+
+        let myval = val("#010203");
+        assert_eq!(*myval.as_other::<RGB>().unwrap(), RGB::new(1, 2, 3));
+        assert_eq!(myval.other_type_name(), Some("rgb"));
+        assert_eq!(myval.hash_other(), Some(0x010203));
+        assert_eq!(myval.compare_other(&myval), None);
+        assert_eq!(myval.as_copy::<RGB>(), Ok(RGB::new(1, 2, 3)));
+        assert_eq!(
+            SyncMoltValue::from_other(RGB::new(255, 0, 0)).as_str(),
+            "#ff0000"
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        // Data reps are carried over without shimmering.
+        let molt = MoltValue::from_list(vec![
+            MoltValue::from_int(1),
+            MoltValue::from_string("a b".to_string()),
+        ]);
+        let sync = SyncMoltValue::from(&molt);
+        assert!(sync.string_rep.get().is_none());
+        assert_eq!(sync.as_list().unwrap()[0].as_int(), Ok(1));
+        assert_eq!(sync.as_str(), "1 {a b}");
+
+        let back = MoltValue::from(&sync);
+        assert_eq!(back.as_list().unwrap()[1].as_str(), "a b");
+        assert_eq!(back.as_str(), "1 {a b}");
+
+        let dict = SyncMoltValue::from(&MoltValue::from_string("a 1".to_string()));
+        assert_eq!(dict.as_dict().unwrap().get("a").unwrap().as_str(), "1");
+        let back = MoltValue::from(&dict);
+        assert_eq!(back.as_dict().unwrap().get("a").unwrap().as_int(), Ok(1));
+
+        // External data reps are carried over by their string reps.
+        let sync = SyncMoltValue::from(&MoltValue::from_other(RGB::new(1, 2, 3)));
+        assert_eq!(sync.other_type_name(), None);
+        assert_eq!(*sync.as_other::<RGB>().unwrap(), RGB::new(1, 2, 3));
+
+        let back = MoltValue::from(&sync);
+        assert_eq!(back.other_type_name(), None);
+        assert_eq!(back.as_str(), "#010203");
+    }
+
+    #[test]
+    fn many_threads() {
+        let shared = Arc::new(val("1 2 3"));
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    for j in 0..1000 {
+                        // Shimmer the value back and forth between data reps.
+                        match (i + j) % 4 {
+                            0 => assert_eq!(shared.as_list().unwrap().len(), 3),
+                            1 => assert_eq!(
+                                shared.as_dict().map(|_| ()),
+                                molt_err!("missing value to go with key")
+                            ),
+                            2 => assert!(shared.as_int().is_err()),
+                            _ => assert_eq!(shared.clone().as_str(), "1 2 3"),
+                        }
+                        assert_eq!(shared.as_str(), "1 2 3");
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // A value with only a data rep gets exactly one string rep.
+        let shared = Arc::new(SyncMoltValue::from_list(vec![
+            SyncMoltValue::from_int(1),
+            SyncMoltValue::from_other(RGB::new(0, 0, 255)),
+        ]));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    let str: *const str = shared.as_str();
+                    assert_eq!(
+                        shared.as_list().unwrap()[1].as_copy::<RGB>(),
+                        Ok(RGB::new(0, 0, 255))
+                    );
+                    str as *const u8 as usize
+                })
+            })
+            .collect();
+
+        let ptrs: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(ptrs.iter().all(|&ptr| ptr == ptrs[0]));
+        assert_eq!(shared.as_str(), "1 #0000ff");
+    }
+}
@@ -43,30 +43,47 @@
 //! Converting from one data rep to another is expensive, as it involves parsing
 //! the string value.  Performance suffers when code switches rapidly from one data
 //! rep to another, e.g., in a tight loop.  The effect, which is known as "shimmering",
-//! can usually be avoided with a little care.  
+//! can usually be avoided with a little care.  Where it can't, a value can be asked to
+//! cache additional data reps using `MoltValue::cache_reps`.
 //!
-//! `MoltValue` handles strings, integers, floating-point values, and lists as
-//! special cases, since they are part of the language and are so frequently used.
+//! `MoltValue` handles strings, integers, floating-point values, booleans, lists,
+//! and dictionaries as special cases, since they are part of the language and are so frequently used.
 //! In addition, a `MoltValue` can also contain any Rust struct that meets
 //! certain requirements.
 //!
 //! # External Types
 //!
-//! Any struct that implements the `std::fmt::Display`, `std::fmt::Debug`,
-//! and `std::str::FromStr` traits can be saved in a `MoltValue`.  The struct's
-//! `Display` and `FromStr` trait implementations are used to do the string
-//! rep/data rep conversions.  In particular:
+//! Any type that implements the [`MoltType`] trait can be saved in a `MoltValue`.
+//! Like the `Tcl_ObjType` in standard TCL, the trait describes the type to the
+//! `MoltValue`: its name, and how to do the string rep/data rep conversions.
+//! In particular:
 //!
-//! * The `Display` implementation is responsible for producing the value's string rep.
+//! * The `to_molt_string` method is responsible for producing the value's string rep.
 //!
-//! * The `FromStr` implementation is responsible for producing the value's data rep from
-//!   a string, and so must be able to parse the `Display` implementation's
-//!   output.
+//! * The `from_molt_str` method is responsible for producing the value's data rep from
+//!   a string, and so must be able to parse the `to_molt_string` method's
+//!   output.  Its error is used as the error message when a `MoltValue` can't be
+//!   converted to the type.
 //!
 //! * The string rep should be chosen so as to fit in well with TCL syntax, lest
 //!   confusion, quoting hell, and comedy should ensue.  (You'll know it when you
 //!   see it.)
 //!
+//! * Optionally, the type can define how its values are compared, hashed, and
+//!   duplicated.
+//!
+//! * The type's methods are never called while the `MoltValue` is borrowed, so they
+//!   may freely access other values, including the one that contains them.
+//!
+//! Types that already implement `Display` and `FromStr` can simply delegate to them.
+//!
+//! When a cheap conversion exists from some other data rep to an external type, it can
+//! be registered with `MoltValue::register_converter`.  `as_other` will then convert
+//! the value's data rep directly, rather than parsing its string rep; the string rep
+//! is retained, so the value's string is unchanged.
+//! Converters are registered for the whole process, so a converter registered at
+//! startup is available on every thread.
+//!
 //! ## Example
 //!
 //! For example, the following code shows how to define an external type implementing
@@ -75,14 +92,27 @@
 //! TODO
 //!
 //! [`MoltValue`]: struct.MoltValue.html
+//! [`MoltType`]: trait.MoltType.html
 
+use crate::dict;
+use crate::dict::MoltDict;
+use crate::list;
+use crate::stats;
+use crate::stats::Event;
 use std::any::Any;
 use std::any::TypeId;
+use std::cell::Cell;
+use std::cell::OnceCell;
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::rc::Rc;
-use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::sync::PoisonError;
+use std::sync::RwLock;
 
 //-----------------------------------------------------------------------------
 // Public Data Types
@@ -92,17 +122,168 @@ use std::str::FromStr;
 pub type MoltList = Vec<MoltValue>;
 pub type MoltInt = i64;
 pub type MoltFloat = f64;
+pub type MoltResult = Result<MoltValue, ResultCode>;
 
-/// The `MoltValue` type. See [the module level documentation](index.html) for more.
+/// The non-OK result of a Molt computation.  Errors and return values are
+/// themselves `MoltValue`s, so that they can be inspected, stored, and re-thrown
+/// like any other value.
 #[derive(Clone, Debug)]
+pub enum ResultCode {
+    /// An error, with its message.
+    Error(MoltValue),
+
+    /// An early return, with the returned value.
+    Return(MoltValue),
+
+    /// A `break` from a loop.
+    Break,
+
+    /// A `continue` in a loop.
+    Continue,
+}
+
+// ResultCodes are compared by the string reps of their values.
+impl PartialEq for ResultCode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ResultCode::Error(a), ResultCode::Error(b)) => a.as_str() == b.as_str(),
+            (ResultCode::Return(a), ResultCode::Return(b)) => a.as_str() == b.as_str(),
+            (ResultCode::Break, ResultCode::Break) => true,
+            (ResultCode::Continue, ResultCode::Continue) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The kind of a `MoltValue`'s current data rep.  See `MoltValue::rep_kind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepKind {
+    /// A `MoltInt`.
+    Int,
+
+    /// A `MoltFloat`.
+    Float,
+
+    /// A `bool`.
+    Bool,
+
+    /// A `MoltList`.
+    List,
+
+    /// A `MoltDict`.
+    Dict,
+
+    /// An external type, with its `MoltType::type_name`.
+    Other(&'static str),
+
+    /// No data rep: the value has only a string rep.
+    None,
+}
+
+// RepKinds are displayed by name, e.g., "int", or the external type's name.
+impl Display for RepKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RepKind::Int => write!(f, "int"),
+            RepKind::Float => write!(f, "float"),
+            RepKind::Bool => write!(f, "bool"),
+            RepKind::List => write!(f, "list"),
+            RepKind::Dict => write!(f, "dict"),
+            RepKind::Other(type_name) => write!(f, "{}", type_name),
+            RepKind::None => write!(f, "none"),
+        }
+    }
+}
+
+/// Determines when converting a `MoltValue` to a different data rep, e.g., with
+/// `as_int`, replaces the value's current data rep with the result.  See
+/// `MoltValue::set_shimmer_policy`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShimmerPolicy {
+    /// Always replace the data rep.  This is the default.
+    #[default]
+    Always,
+
+    /// Never replace the data rep: convert, but don't cache the result.
+    Never,
+
+    /// Replace the data rep unless it is a list, a dictionary, or an external type,
+    /// which are relatively expensive to recompute.
+    KeepExpensive,
+
+    /// Replace the data rep only if the value has none, i.e., if it has only a
+    /// string rep.
+    IfNone,
+}
+
+/// The `MoltValue` type. See [the module level documentation](index.html) for more.
 pub struct MoltValue {
-    string_rep: RefCell<Option<Rc<String>>>,
+    string_rep: OnceCell<Rc<String>>,
     data_rep: RefCell<Datum>,
+
+    // Additional data reps, if the value caches them.  See `cache_reps`.
+    alt_reps: RefCell<Option<Box<AltReps>>>,
+
+    // When conversions may replace the data rep.
+    policy: Cell<ShimmerPolicy>,
+}
+
+// Clones share the string rep; the data rep is duplicated as its type requires, without
+// holding a borrow.  Clones have the same shimmer policy, and cache the same number
+// of additional data reps, but start with none.
+impl Clone for MoltValue {
+    fn clone(&self) -> Self {
+        let alt_reps = self
+            .alt_reps
+            .borrow()
+            .as_ref()
+            .map(|alt_reps| Box::new(AltReps::new(alt_reps.slots)));
+
+        MoltValue {
+            string_rep: self.string_rep.clone(),
+            data_rep: RefCell::new(self.data().clone()),
+            alt_reps: RefCell::new(alt_reps),
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+// Shows both legs of the stork: the string rep, if any, and the data rep, plus any
+// cached data reps.
+impl Debug for MoltValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut out = f.debug_struct("MoltValue");
+
+        match self.string_rep.get() {
+            Some(str) => out.field("string_rep", &str.as_str()),
+            None => out.field("string_rep", &format_args!("<none>")),
+        };
+
+        out.field("data_rep", &self.data());
+
+        let alt_reps: Option<Vec<Datum>> = self
+            .alt_reps
+            .borrow()
+            .as_ref()
+            .map(|alt_reps| alt_reps.reps.iter().map(Datum::share).collect());
+
+        if let Some(alt_reps) = alt_reps {
+            out.field("alt_reps", &alt_reps);
+        }
+
+        out.finish()
+    }
+}
+
+impl AsRef<str> for MoltValue {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
 }
 
 impl Display for MoltValue {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.as_string())
+        f.write_str(self.as_str())
     }
 }
 
@@ -120,35 +301,39 @@ impl MoltValue {
     /// TODO
     pub fn from_string(str: String) -> MoltValue {
         MoltValue {
-            string_rep: RefCell::new(Some(Rc::new(str))),
+            string_rep: OnceCell::from(Rc::new(str)),
             data_rep: RefCell::new(Datum::None),
+            alt_reps: RefCell::new(None),
+            policy: Cell::new(ShimmerPolicy::Always),
         }
     }
-    /// Returns the value's string representation as a reference-counted
-    /// string.
+
+    /// Returns the value's string representation as a `&str`, computing it from the
+    /// data rep if necessary.
     ///
     /// **Note**: this is the standard way of retrieving a `MoltValue`'s
-    /// string rep, as unlike `to_string` it doesn't create a new `String`.
+    /// string rep.  Once computed, the string rep never changes, so the borrow
+    /// involves neither a runtime borrow check nor reference counting, and unlike
+    /// `to_string` it doesn't create a new `String`.
     ///
     /// # Example
     ///
     /// TODO
-    fn as_string(&self) -> Rc<String> {
+    pub fn as_str(&self) -> &str {
         // FIRST, if there's already a string, return it.
-        let mut string_ref = self.string_rep.borrow_mut();
-
-        if let Some(str) = &*string_ref {
-            return Rc::clone(str);
+        if let Some(str) = self.string_rep.get() {
+            return str;
         }
 
-        // NEXT, if there's no string there must be data.  Convert the data to a string,
-        // and save it for next time.
-        let data_ref = self.data_rep.borrow();
-        let new_string = Rc::new((*data_ref).to_string());
-
-        *string_ref = Some(new_string.clone());
-
-        new_string
+        // NEXT, if there's no string there must be data.  Convert the data to a
+        // string and save it for next time.  Producing the string may itself have
+        // produced this value's string rep; if so, it's the same string, and the
+        // first one wins.
+        let data = self.data();
+        stats::count(data.kind_name(), Event::StringRep);
+        let str = data.to_string();
+        let _ = self.string_rep.set(Rc::new(str));
+        self.string_rep.get().unwrap()
     }
 
     /// Creates a new `MoltValue` whose data representation is a `MoltInt`.
@@ -158,47 +343,98 @@ impl MoltValue {
     /// TODO
     pub fn from_int(int: MoltInt) -> MoltValue {
         MoltValue {
-            string_rep: RefCell::new(None),
+            string_rep: OnceCell::new(),
             data_rep: RefCell::new(Datum::Int(int)),
+            alt_reps: RefCell::new(None),
+            policy: Cell::new(ShimmerPolicy::Always),
         }
     }
 
     /// Tries to return the `MoltValue` as a `MoltInt`, parsing the
     /// value's string representation if necessary.
     ///
-    /// TODO: Need to return Molt-compatible Err's.
-    ///
     /// # Example
     ///
     /// TODO
-    pub fn as_int(&self) -> Result<MoltInt, String> {
-        let mut data_ref = self.data_rep.borrow_mut();
-        let mut string_ref = self.string_rep.borrow_mut();
-
+    pub fn as_int(&self) -> Result<MoltInt, ResultCode> {
         // FIRST, if we have an integer then just return it.
-        if let Datum::Int(int) = *data_ref {
-            return dbg!(Ok(int));
+        if let Datum::Int(int) = *self.data_rep.borrow() {
+            stats::count("int", Event::CacheHit);
+            return Ok(int);
         }
 
-        // NEXT, if we don't have a string_rep, get one from the current
-        // data_rep.
-        if (*string_ref).is_none() {
-            *string_ref = Some(Rc::new(data_ref.to_string()));
+        // NEXT, if we have a cached integer, use it.
+        if let Some(Datum::Int(int)) = self.promote(|datum| matches!(datum, Datum::Int(_))) {
+            stats::count("int", Event::CacheHit);
+            return Ok(int);
         }
 
-        // NEXT, Try to parse the string_rep as an integer
-        if let Some(str) = &*string_ref {
-            // TODO: Uses standard Rust integer parsing.  Need to use the
-            // TCL algorithm; see Interp::get_int.
-            if let Ok(int) = str.parse::<MoltInt>() {
-                *data_ref = Datum::Int(int);
-                return Ok(int);
-            }
+        // NEXT, Try to parse the string_rep as an integer, getting the string_rep
+        // from the current data rep if need be.
+        let int = MoltValue::get_int(self.parse_str("int"))?;
+        self.cache(Datum::Int(int));
+        Ok(int)
+    }
+
+    /// Parses a string as a `MoltInt`, following the TCL integer syntax.
+    ///
+    /// The string may have leading and trailing whitespace and an optional sign,
+    /// followed by a radix prefix (`0x` for hex, `0o` for octal, `0b` for binary,
+    /// or none for decimal) and the digits, which may be separated by single
+    /// underscores, e.g., `1_000_000`.  It's an error if the value doesn't fit in
+    /// a `MoltInt`.
+    ///
+    /// This is the algorithm used when shimmering a string rep to a `Datum::Int`;
+    /// client code should normally use `as_int` instead.
+    pub fn get_int(arg: &str) -> Result<MoltInt, ResultCode> {
+        let not_an_int = || expected("integer", arg);
+        let mut rest = arg.trim_matches(is_tcl_white);
+
+        // FIRST, get the sign.
+        let negative = rest.starts_with('-');
+
+        if negative || rest.starts_with('+') {
+            rest = &rest[1..];
+        }
+
+        // NEXT, get the radix.
+        let radix = match rest.get(0..2) {
+            Some("0x") | Some("0X") => 16,
+            Some("0o") | Some("0O") => 8,
+            Some("0b") | Some("0B") => 2,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            rest = &rest[2..];
         }
 
-        // NEXT, we can't interpret it as an integer; return an error.
-        // TODO: Use the correct error message.
-        Err("Not an integer".to_string())
+        // NEXT, accumulate the digits; underscores are allowed only between
+        // digits.
+        let digits = strip_separators(rest, radix).ok_or_else(not_an_int)?;
+        let mut magnitude: u64 = 0;
+
+        for c in digits.chars() {
+            magnitude = magnitude
+                .checked_mul(radix as u64)
+                .and_then(|m| m.checked_add(c.to_digit(radix).unwrap() as u64))
+                .ok_or_else(too_large)?;
+        }
+
+        // NEXT, apply the sign, checking for overflow.
+        if negative {
+            if magnitude <= MoltInt::MAX as u64 {
+                Ok(-(magnitude as MoltInt))
+            } else if magnitude == MoltInt::MIN.unsigned_abs() {
+                Ok(MoltInt::MIN)
+            } else {
+                Err(too_large())
+            }
+        } else if magnitude <= MoltInt::MAX as u64 {
+            Ok(magnitude as MoltInt)
+        } else {
+            Err(too_large())
+        }
     }
 
     /// Creates a new `MoltValue` whose data representation is a `MoltFloat`.
@@ -208,47 +444,288 @@ impl MoltValue {
     /// TODO
     pub fn from_float(flt: MoltFloat) -> MoltValue {
         MoltValue {
-            string_rep: RefCell::new(None),
+            string_rep: OnceCell::new(),
             data_rep: RefCell::new(Datum::Flt(flt)),
+            alt_reps: RefCell::new(None),
+            policy: Cell::new(ShimmerPolicy::Always),
         }
     }
 
+    /// Formats a `MoltFloat` as a string, following the TCL conventions.
+    ///
+    /// The output has the fewest digits that will parse back to the same value,
+    /// and always includes a decimal point or an exponent so that it can't be
+    /// mistaken for an integer: `7.0` rather than `7`.  Very large and very small
+    /// magnitudes use exponential notation, e.g., `1e+17` and `1e-5`; infinities
+    /// and NaN are spelled `Inf`, `-Inf`, and `NaN`.
+    ///
+    /// This is the algorithm used when computing the string rep of a `Datum::Flt`.
+    pub fn fmt_float(flt: MoltFloat) -> String {
+        // FIRST, handle the special cases.
+        if flt.is_nan() {
+            return "NaN".to_string();
+        } else if flt.is_infinite() {
+            return if flt > 0.0 { "Inf" } else { "-Inf" }.to_string();
+        }
+
+        // NEXT, get the shortest round-trippable digits and the decimal exponent
+        // from Rust's exponential formatting, e.g., "-1.25e-7".
+        let sci = format!("{:e}", flt);
+        let (mantissa, exp) = sci.split_at(sci.find('e').unwrap());
+        let exponent: i32 = exp[1..].parse().unwrap();
+        let (sign, mantissa) = if let Some(m) = mantissa.strip_prefix('-') {
+            ("-", m)
+        } else {
+            ("", mantissa)
+        };
+        let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+
+        let mut out = sign.to_string();
+
+        if !(-4..=16).contains(&exponent) {
+            // NEXT, use exponential notation: d.ddde+X.
+            out.push_str(&digits[0..1]);
+
+            if digits.len() > 1 {
+                out.push('.');
+                out.push_str(&digits[1..]);
+            }
+
+            out.push_str(&format!("e{:+}", exponent));
+        } else if exponent < 0 {
+            // NEXT, use fixed notation with leading zeros: 0.000ddd.
+            out.push_str("0.");
+
+            for _ in 0..(-exponent - 1) {
+                out.push('0');
+            }
+
+            out.push_str(&digits);
+        } else {
+            // NEXT, use fixed notation: ddd.ddd, padding the integer part with zeros
+            // as needed, and always including a fractional part.
+            let int_len = exponent as usize + 1;
+
+            if digits.len() > int_len {
+                out.push_str(&digits[..int_len]);
+                out.push('.');
+                out.push_str(&digits[int_len..]);
+            } else {
+                out.push_str(&digits);
+
+                for _ in digits.len()..int_len {
+                    out.push('0');
+                }
+
+                out.push_str(".0");
+            }
+        }
+
+        out
+    }
+
     /// Tries to return the `MoltValue` as a `MoltFloat`, parsing the
     /// value's string representation if necessary.
     ///
-    /// TODO: Need to return Molt-compatible Err's.
-    ///
     /// # Example
     ///
     /// TODO
-    pub fn as_float(&self) -> Result<MoltFloat, String> {
-        let mut data_ref = self.data_rep.borrow_mut();
-        let mut string_ref = self.string_rep.borrow_mut();
-
+    pub fn as_float(&self) -> Result<MoltFloat, ResultCode> {
         // FIRST, if we have a float then just return it.
-        if let Datum::Flt(flt) = *data_ref {
+        if let Datum::Flt(flt) = *self.data_rep.borrow() {
+            stats::count("float", Event::CacheHit);
+            return Ok(flt);
+        }
+
+        // NEXT, if we have a cached float, use it.
+        if let Some(Datum::Flt(flt)) = self.promote(|datum| matches!(datum, Datum::Flt(_))) {
+            stats::count("float", Event::CacheHit);
             return Ok(flt);
         }
 
-        // NEXT, if we don't have a string_rep, get one from the current
-        // data rep.
-        if (*string_ref).is_none() {
-            *string_ref = Some(Rc::new(data_ref.to_string()));
+        // NEXT, Try to parse the string_rep as a float, getting the string_rep
+        // from the current data rep if need be.
+        let flt = MoltValue::get_float(self.parse_str("float"))?;
+        self.cache(Datum::Flt(flt));
+        Ok(flt)
+    }
+
+    /// Parses a string as a `MoltFloat`, following the TCL syntax for doubles.
+    ///
+    /// The string may have leading and trailing whitespace and an optional sign,
+    /// followed by a decimal number with an optional fraction and exponent, e.g.,
+    /// `1.5e-3`; an integer in any of the forms accepted by `get_int`, e.g., `0x10`;
+    /// or one of `Inf`, `Infinity`, or `NaN` in any case.  Underscores may separate
+    /// the digits of the integer part or fraction, but not of the exponent, and `NaN`
+    /// may not have a sign.  It's an error if a finite number is too large to
+    /// represent.
+    ///
+    /// This is the algorithm used when shimmering a string rep to a `Datum::Flt`;
+    /// client code should normally use `as_float` instead.
+    pub fn get_float(arg: &str) -> Result<MoltFloat, ResultCode> {
+        let not_a_float = || expected("floating-point number", arg);
+        let trimmed = arg.trim_matches(is_tcl_white);
+
+        // FIRST, get the sign.
+        let (negative, rest) = match trimmed.chars().next() {
+            Some('-') => (true, &trimmed[1..]),
+            Some('+') => (false, &trimmed[1..]),
+            _ => (false, trimmed),
+        };
+        let sign = if negative { -1.0 } else { 1.0 };
+
+        // NEXT, handle the special values.
+        let lower = rest.to_lowercase();
+
+        if lower == "inf" || lower == "infinity" {
+            return Ok(sign * MoltFloat::INFINITY);
+        } else if lower == "nan" && rest.len() == trimmed.len() {
+            return Ok(MoltFloat::NAN);
         }
 
-        // NEXT, Try to parse the string_rep as a float
-        if let Some(str) = &*string_ref {
-            // TODO: Currently uses the standard Rust parser.  That may
-            // be OK, but I need to check.
-            if let Ok(flt) = str.parse::<MoltFloat>() {
-                *data_ref = Datum::Flt(flt);
-                return Ok(flt);
+        // NEXT, handle integers with a radix prefix.  These can't overflow
+        // the way a MoltInt can.
+        let radix = match rest.get(0..2) {
+            Some("0x") | Some("0X") => 16,
+            Some("0o") | Some("0O") => 8,
+            Some("0b") | Some("0B") => 2,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            let digits = strip_separators(&rest[2..], radix).ok_or_else(not_a_float)?;
+            let mut flt: MoltFloat = 0.0;
+
+            for c in digits.chars() {
+                flt = flt * radix as MoltFloat + c.to_digit(radix).unwrap() as MoltFloat;
             }
+
+            return check_float(sign * flt);
         }
 
-        // NEXT, we can't interpret it as an integer; return an error.
-        // TODO: need to use the right error message.
-        Err("Not a float".to_string())
+        // NEXT, validate the decimal syntax: digits, an optional fraction, and
+        // an optional exponent.  The mantissa must contain at least one digit.
+        let (mantissa, exponent) = match rest.find(['e', 'E']) {
+            Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+            None => (rest, None),
+        };
+        let (int_part, frac_part) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+            None => (mantissa, ""),
+        };
+
+        if int_part.is_empty() && frac_part.is_empty() {
+            return Err(not_a_float());
+        }
+
+        let mut normalized = String::new();
+
+        if negative {
+            normalized.push('-');
+        }
+
+        for part in &[int_part, frac_part] {
+            if !part.is_empty() {
+                normalized.push_str(&strip_separators(part, 10).ok_or_else(not_a_float)?);
+            }
+            normalized.push('.');
+        }
+        normalized.pop();
+
+        if let Some(exponent) = exponent {
+            let (exp_sign, exp_digits) = match exponent.chars().next() {
+                Some('-') => ("-", &exponent[1..]),
+                Some('+') => ("", &exponent[1..]),
+                _ => ("", exponent),
+            };
+            if exp_digits.is_empty() || !exp_digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(not_a_float());
+            }
+
+            normalized.push('e');
+            normalized.push_str(exp_sign);
+            normalized.push_str(exp_digits);
+        }
+
+        // NEXT, the syntax is good; let Rust do the conversion.
+        check_float(normalized.parse::<MoltFloat>().map_err(|_| not_a_float())?)
+    }
+
+    /// Creates a new `MoltValue` whose data representation is a `bool`.
+    ///
+    /// # Example
+    ///
+    /// TODO
+    pub fn from_bool(flag: bool) -> MoltValue {
+        MoltValue {
+            string_rep: OnceCell::new(),
+            data_rep: RefCell::new(Datum::Bool(flag)),
+            alt_reps: RefCell::new(None),
+            policy: Cell::new(ShimmerPolicy::Always),
+        }
+    }
+
+    /// Tries to return the `MoltValue` as a `bool`, parsing the
+    /// value's string representation if necessary.
+    ///
+    /// # Example
+    ///
+    /// TODO
+    pub fn as_bool(&self) -> Result<bool, ResultCode> {
+        // FIRST, if we have a boolean then just return it.
+        if let Datum::Bool(flag) = *self.data_rep.borrow() {
+            stats::count("bool", Event::CacheHit);
+            return Ok(flag);
+        }
+
+        // NEXT, if we have a cached boolean, use it.
+        if let Some(Datum::Bool(flag)) = self.promote(|datum| matches!(datum, Datum::Bool(_))) {
+            stats::count("bool", Event::CacheHit);
+            return Ok(flag);
+        }
+
+        // NEXT, Try to parse the string_rep as a boolean, getting the string_rep
+        // from the current data rep if need be.
+        let flag = MoltValue::get_bool(self.parse_str("bool"))?;
+        self.cache(Datum::Bool(flag));
+        Ok(flag)
+    }
+
+    /// Parses a string as a `bool`, following the TCL boolean syntax.
+    ///
+    /// The string may be any integer, as accepted by `get_int` but without leading or
+    /// trailing whitespace, in which case zero is false and anything else is true; or
+    /// any unique prefix of `true`, `false`, `yes`, `no`, `on`, or `off`, in any case.
+    ///
+    /// This is the algorithm used when shimmering a string rep to a `Datum::Bool`;
+    /// client code should normally use `as_bool` instead.
+    pub fn get_bool(arg: &str) -> Result<bool, ResultCode> {
+        // FIRST, is it an integer?  Unlike `get_int`, whitespace isn't allowed.
+        if !arg.starts_with(is_tcl_white) && !arg.ends_with(is_tcl_white) {
+            if let Ok(int) = MoltValue::get_int(arg) {
+                return Ok(int != 0);
+            }
+        }
+
+        // NEXT, is it a unique prefix of one of the boolean words?  The prefix "o" is
+        // ambiguous, and so at least two letters are needed for "on" and "off".
+        let word = arg.to_lowercase();
+        let words = [
+            ("true", 1, true),
+            ("false", 1, false),
+            ("yes", 1, true),
+            ("no", 1, false),
+            ("on", 2, true),
+            ("off", 2, false),
+        ];
+
+        for (name, min_len, flag) in &words {
+            if word.len() >= *min_len && name.starts_with(&word) {
+                return Ok(*flag);
+            }
+        }
+
+        Err(expected("boolean value", arg))
     }
 
     /// Creates a new `MoltValue` whose data representation is a `MoltList`.
@@ -258,146 +735,644 @@ impl MoltValue {
     /// TODO
     pub fn from_list(list: MoltList) -> MoltValue {
         MoltValue {
-            string_rep: RefCell::new(None),
+            string_rep: OnceCell::new(),
             data_rep: RefCell::new(Datum::List(Rc::new(list))),
+            alt_reps: RefCell::new(None),
+            policy: Cell::new(ShimmerPolicy::Always),
         }
     }
 
     /// Tries to return the `MoltValue` as a `MoltList`, parsing the
     /// value's string representation if necessary.
     ///
-    /// TODO: Need to return Molt-compatible Err's.
-    /// TODO: Need to add list parsing.
+    /// # Example
+    ///
+    /// TODO
+    pub fn as_list(&self) -> Result<Rc<MoltList>, ResultCode> {
+        // FIRST, if we have a list then just return it.
+        if let Datum::List(list) = &*self.data_rep.borrow() {
+            stats::count("list", Event::CacheHit);
+            return Ok(list.clone());
+        }
+
+        // NEXT, if we have a cached list, use it.
+        if let Some(Datum::List(list)) = self.promote(|datum| matches!(datum, Datum::List(_))) {
+            stats::count("list", Event::CacheHit);
+            return Ok(list);
+        }
+
+        // NEXT, parse the string_rep as a list, getting the string_rep from the
+        // current data rep if need be.  Any string is a list unless
+        // its braces or quotes are unbalanced.
+        let list = Rc::new(list::get_list(self.parse_str("list"))?);
+        self.cache(Datum::List(list.clone()));
+        Ok(list)
+    }
+
+    /// Creates a new `MoltValue` whose data representation is a `MoltDict`.
+    ///
+    /// # Example
+    ///
+    /// TODO
+    pub fn from_dict(dict: MoltDict) -> MoltValue {
+        MoltValue {
+            string_rep: OnceCell::new(),
+            data_rep: RefCell::new(Datum::Dict(Rc::new(dict))),
+            alt_reps: RefCell::new(None),
+            policy: Cell::new(ShimmerPolicy::Always),
+        }
+    }
+
+    /// Tries to return the `MoltValue` as a `MoltDict`, parsing the
+    /// value's string representation if necessary.
     ///
     /// # Example
     ///
     /// TODO
-    pub fn as_list(&self) -> Result<Rc<MoltList>, String> {
-        let data_ref = self.data_rep.borrow_mut();
+    pub fn as_dict(&self) -> Result<Rc<MoltDict>, ResultCode> {
+        // FIRST, if we have a dict then just return it.
+        if let Datum::Dict(dict) = &*self.data_rep.borrow() {
+            stats::count("dict", Event::CacheHit);
+            return Ok(dict.clone());
+        }
 
-        if let Datum::List(list) = &*data_ref {
-            Ok(list.clone())
-        // } else if let Some(_str) = &self.string_rep {
-        //     panic!("list string_rep not defined!");
-        } else {
-            Err("Not a list".to_string())
+        // NEXT, if we have a cached dict, use it.
+        if let Some(Datum::Dict(dict)) = self.promote(|datum| matches!(datum, Datum::Dict(_))) {
+            stats::count("dict", Event::CacheHit);
+            return Ok(dict);
         }
+
+        // NEXT, parse the string_rep as a dict, getting the string_rep from the
+        // current data rep if need be.
+        let dict = Rc::new(dict::get_dict(self.parse_str("dict"))?);
+        self.cache(Datum::Dict(dict.clone()));
+        Ok(dict)
     }
 
-    /// Creates a new `MoltValue` containing the given value of some user type.
+    /// Creates a new `MoltValue` containing the given value of some external type.
     ///
-    /// The type must implement `Display`, `Debug`, and `FromStr`, and the
-    /// `Display` output must be compatible with the `FromStr` parser (and with
-    /// TCL syntax).  The value will be reference counted.
-    pub fn from_other<T: 'static>(value: T) -> MoltValue
-    where
-        T: Display + Debug,
-    {
+    /// The type must implement `MoltType`.  The value will be reference counted.
+    pub fn from_other<T: MoltType>(value: T) -> MoltValue {
         MoltValue {
-            string_rep: RefCell::new(None),
+            string_rep: OnceCell::new(),
             data_rep: RefCell::new(Datum::Other(Rc::new(value))),
+            alt_reps: RefCell::new(None),
+            policy: Cell::new(ShimmerPolicy::Always),
         }
     }
 
-    /// Tries to interpret the `MoltValue` as a value of type `T`.
+    /// Tries to interpret the `MoltValue` as a value of external type `T`,
+    /// parsing the value's string representation if necessary.
     ///
     /// The value is returned as an `Rc<T>`, as this allows the client to
-    /// use the value freely.
-    ///
-    /// This method returns `Option` rather than `Result` because it is up
-    /// to the caller to provide a meaningful error message.  It is normal
-    /// for externally defined types to wrap this function in a function
-    /// that does so.
+    /// use the value freely.  If the string rep can't be parsed, the error
+    /// message is the one returned by `T::from_molt_str`.
     ///
     /// # Example
     ///
     /// TODO
-    pub fn as_other<T: 'static>(&self) -> Option<Rc<T>>
-    where
-        T: Display + Debug + FromStr,
-    {
-        let mut string_ref = self.string_rep.borrow_mut();
-        let mut data_ref = self.data_rep.borrow_mut();
-
+    pub fn as_other<T: MoltType>(&self) -> Result<Rc<T>, ResultCode> {
         // FIRST, if we have the desired type, return it.
-        if let Datum::Other(other) = &*data_ref {
+        let data = self.data();
+
+        if let Datum::Other(other) = &data {
             // other is an &Rc<MoltAny>
-            let result = other.clone().downcast::<T>();
+            if let Ok(out) = other.clone().downcast::<T>() {
+                stats::count(T::type_name(), Event::CacheHit);
+                return Ok(out);
+            }
+        }
+
+        // NEXT, if we have a cached T, use it.
+        let cached = self.promote(|datum| match datum {
+            Datum::Other(other) => other.is::<T>(),
+            _ => false,
+        });
 
-            if result.is_ok() {
-                // Let's be sure we're really getting what we wanted.
-                let out: Rc<T> = result.unwrap();
-                return Some(out);
+        if let Some(Datum::Other(other)) = cached {
+            if let Ok(out) = other.downcast::<T>() {
+                stats::count(T::type_name(), Event::CacheHit);
+                return Ok(out);
             }
         }
 
-        // NEXT, if we don't have a string_rep, get one.
-        if (*string_ref).is_none() {
-            *string_ref = Some(Rc::new(data_ref.to_string()));
+        // NEXT, if there's a converter from the current data rep to a T, use it.
+        let converted = data.as_any().and_then(|any| {
+            converter(any.type_id(), TypeId::of::<T>())
+                .and_then(|convert| convert(any))
+                .and_then(|out| out.downcast::<T>().ok())
+        });
+
+        if let Some(out) = converted {
+            stats::count(T::type_name(), Event::Conversion);
+
+            // The string rep is retained, so produce it from the old data rep first.
+            self.as_str();
+            self.cache(Datum::Other(out.clone()));
+            return Ok(out);
         }
 
-        // NEXT, can we parse it as a T?  If so, save it back to
-        // the data_rep, and return it.
-        if let Some(str) = &*string_ref {
-            if let Ok(tval) = str.parse::<T>() {
-                let tval = Rc::new(tval);
-                let out = tval.clone();
-                *data_ref = Datum::Other(Rc::new(tval));
-                return Some(out);
+        // NEXT, can we parse the string_rep as a T?  If so, save it back to
+        // the data_rep, and return it.  Note that the `Rc<T>` itself is
+        // saved as the `Rc<dyn MoltAny>`, so that it can be downcast next time.
+        match T::from_molt_str(self.parse_str(T::type_name())) {
+            Ok(tval) => {
+                let out = Rc::new(tval);
+                self.cache(Datum::Other(out.clone()));
+                Ok(out)
             }
+            Err(err) => molt_err!("{}", err),
+        }
+    }
+
+    /// Tries to interpret the `MoltValue` as a value of external type `T`, returning
+    /// a copy.
+    ///
+    /// # Example
+    ///
+    /// TODO
+    pub fn as_copy<T: MoltType + Copy>(&self) -> Result<T, ResultCode> {
+        self.as_other::<T>().map(|out| *out)
+    }
+
+    /// Compares this value's external data rep with another value's, using the
+    /// type's `MoltType::compare` method.  Returns `None` unless both values
+    /// currently have data reps of the same external type, and that type defines an
+    /// ordering.
+    pub fn compare_other(&self, other: &MoltValue) -> Option<Ordering> {
+        match (&self.data(), &other.data()) {
+            (Datum::Other(a), Datum::Other(b)) => a.compare_any(b.as_any()),
+            _ => None,
+        }
+    }
+
+    /// Returns the hash of this value's external data rep, using the type's
+    /// `MoltType::hash_value` method.  Returns `None` unless the value currently has
+    /// an external data rep whose type defines a hash.
+    pub fn hash_other(&self) -> Option<u64> {
+        match &self.data() {
+            Datum::Other(other) => other.hash_any(),
+            _ => None,
+        }
+    }
+
+    /// Registers a direct conversion from data rep `S` to external type `T`, for use by
+    /// `as_other`.  `S` may be another external type, or one of the standard
+    /// data reps: `MoltInt`, `MoltFloat`, `bool`, `MoltList`, or `MoltDict`.
+    ///
+    /// The converter must produce the same value that `T::from_molt_str` would produce
+    /// from the source value's string rep, as the string rep is retained.  If it
+    /// returns `None`, `as_other` falls back to parsing the string rep.  Converters
+    /// are registered for the whole process, and are used on every thread;
+    /// registering a second converter for the same pair of types replaces the first.
+    pub fn register_converter<S: 'static, T: MoltType>(convert: fn(&S) -> Option<T>) {
+        let converter: Converter = Arc::new(move |any: &dyn Any| {
+            any.downcast_ref::<S>()
+                .and_then(convert)
+                .map(|out| Rc::new(out) as Rc<dyn MoltAny>)
+        });
+
+        converters()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert((TypeId::of::<S>(), TypeId::of::<T>()), converter);
+    }
+
+    /// Returns the `MoltType::type_name` of this value's external data rep, or `None`
+    /// if the value doesn't currently have an external data rep.
+    pub fn other_type_name(&self) -> Option<&'static str> {
+        match &self.data() {
+            Datum::Other(other) => Some(other.type_name()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the value could be interpreted as a `MoltInt`, without changing
+    /// its data rep.  See `try_int_no_cache`.
+    pub fn is_int(&self) -> bool {
+        self.try_int_no_cache().is_ok()
+    }
+
+    /// Returns true if the value could be interpreted as a `MoltFloat`, without
+    /// changing its data rep.
+    pub fn is_float(&self) -> bool {
+        self.find_rep(|datum| matches!(datum, Datum::Flt(_)))
+            .is_some()
+            || MoltValue::get_float(self.parse_str("float")).is_ok()
+    }
+
+    /// Returns true if the value could be interpreted as a `bool`, without changing
+    /// its data rep.
+    pub fn is_bool(&self) -> bool {
+        self.find_rep(|datum| matches!(datum, Datum::Bool(_)))
+            .is_some()
+            || MoltValue::get_bool(self.parse_str("bool")).is_ok()
+    }
+
+    /// Returns true if the value could be interpreted as a `MoltList`, without
+    /// changing its data rep.
+    pub fn is_list(&self) -> bool {
+        self.find_rep(|datum| matches!(datum, Datum::List(_)))
+            .is_some()
+            || list::get_list(self.parse_str("list")).is_ok()
+    }
+
+    /// Returns true if the value could be interpreted as a `MoltDict`, without
+    /// changing its data rep.
+    pub fn is_dict(&self) -> bool {
+        self.find_rep(|datum| matches!(datum, Datum::Dict(_)))
+            .is_some()
+            || dict::get_dict(self.parse_str("dict")).is_ok()
+    }
+
+    /// Returns true if the value could be interpreted as a value of external type `T`,
+    /// without changing its data rep.
+    pub fn can_be<T: MoltType>(&self) -> bool {
+        let found = self.find_rep(|datum| match datum {
+            Datum::Other(other) => other.is::<T>(),
+            _ => false,
+        });
+
+        found.is_some() || T::from_molt_str(self.parse_str(T::type_name())).is_ok()
+    }
+
+    /// Returns the value as a `MoltInt`, like `as_int`, but without changing its data
+    /// rep: if the value doesn't already have an integer data rep, the string rep is
+    /// parsed and the result is discarded.
+    ///
+    /// The probes, like this one and `is_list`, may compute the value's string rep,
+    /// but never replace its data rep.  This allows type-dispatching code to inspect a
+    /// value without losing an expensive data rep.
+    pub fn try_int_no_cache(&self) -> Result<MoltInt, ResultCode> {
+        match self.find_rep(|datum| matches!(datum, Datum::Int(_))) {
+            Some(Datum::Int(int)) => Ok(int),
+            _ => MoltValue::get_int(self.parse_str("int")),
         }
+    }
+
+    /// Returns true if the value currently has a string rep.  A value always has a
+    /// string rep, a data rep, or both.
+    pub fn has_string_rep(&self) -> bool {
+        self.string_rep.get().is_some()
+    }
+
+    /// Returns the kind of the value's current data rep.
+    pub fn rep_kind(&self) -> RepKind {
+        match &*self.data_rep.borrow() {
+            Datum::Int(_) => RepKind::Int,
+            Datum::Flt(_) => RepKind::Float,
+            Datum::Bool(_) => RepKind::Bool,
+            Datum::List(_) => RepKind::List,
+            Datum::Dict(_) => RepKind::Dict,
+            Datum::Other(other) => RepKind::Other(other.type_name()),
+            Datum::None => RepKind::None,
+        }
+    }
+
+    /// Returns the strong reference count of the value's string rep, which is shared
+    /// with the value's clones, or `None` if it has no string rep.
+    pub fn string_rep_strong_count(&self) -> Option<usize> {
+        self.string_rep.get().map(Rc::strong_count)
+    }
+
+    /// Returns the strong reference count of the value's data rep, or `None` if the
+    /// data rep isn't reference counted, i.e., if it isn't a list, a dictionary, or an
+    /// external type.
+    pub fn data_rep_strong_count(&self) -> Option<usize> {
+        match &*self.data_rep.borrow() {
+            Datum::List(list) => Some(Rc::strong_count(list)),
+            Datum::Dict(dict) => Some(Rc::strong_count(dict)),
+            Datum::Other(other) => Some(Rc::strong_count(other)),
+            _ => None,
+        }
+    }
+
+    /// Returns a description of the value's reps, for debugging, in the style of
+    /// TCL's `tcl::unsupported::representation` command, e.g.,
+    /// `value is a list with a refcount of 1, string representation "a b c"`.  Long
+    /// string reps are truncated.
+    pub fn describe(&self) -> String {
+        let mut out = match self.rep_kind() {
+            RepKind::None => "value is a pure string".to_string(),
+            kind => format!("value is a {}", kind),
+        };
+
+        if let Some(count) = self.data_rep_strong_count() {
+            out.push_str(&format!(" with a refcount of {}", count));
+        }
+
+        match self.string_rep.get() {
+            Some(str) => out.push_str(&format!(
+                ", string representation \"{}\"",
+                truncate(str, 50)
+            )),
+            None => out.push_str(", no string representation"),
+        }
+
+        out
+    }
+
+    /// Returns the value's shimmer policy.
+    pub fn shimmer_policy(&self) -> ShimmerPolicy {
+        self.policy.get()
+    }
+
+    /// Sets the value's shimmer policy, which determines whether conversions like
+    /// `as_int` replace the value's data rep with their results.  The conversions
+    /// return the same results either way.
+    pub fn set_shimmer_policy(&self, policy: ShimmerPolicy) {
+        self.policy.set(policy);
+    }
+
+    /// Calls the function with the value's shimmer policy temporarily set to
+    /// `policy`, and returns its result.  This allows the policy to be set for a
+    /// single conversion, e.g., `value.with_policy(ShimmerPolicy::Never, |v| v.as_int())`.
+    /// The old policy is restored even if the function panics.
+    pub fn with_policy<R>(&self, policy: ShimmerPolicy, f: impl FnOnce(&MoltValue) -> R) -> R {
+        // Restores the old policy when dropped.
+        struct Restore<'a>(&'a Cell<ShimmerPolicy>, ShimmerPolicy);
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.1);
+            }
+        }
+
+        let _restore = Restore(&self.policy, self.policy.replace(policy));
+        f(self)
+    }
+
+    /// Allows the value to cache up to `slots` data reps in addition to its current one,
+    /// so that code that alternates between data reps, e.g., calling `as_int` and
+    /// `as_list` in turn, needn't reparse the string rep each time.  By default,
+    /// values cache no additional data reps.
+    ///
+    /// A value caches at most one data rep of each kind: `MoltInt`, `MoltFloat`,
+    /// `bool`, `MoltList`, `MoltDict`, or a particular external type.  When a
+    /// conversion replaces the current data rep, the old one is cached as the most
+    /// recently used.  When a cached data rep is requested, it becomes the current
+    /// data rep, and the current one is cached in its place.  When there are more than
+    /// `slots` cached data reps, the least recently used are evicted.
+    ///
+    /// Reducing the number of slots evicts any excess data reps; setting it to 0
+    /// evicts them all.
+    pub fn cache_reps(&self, slots: usize) {
+        let evicted = {
+            let mut alt_ref = self.alt_reps.borrow_mut();
+
+            if slots == 0 {
+                alt_ref.take().map(|alt_reps| alt_reps.reps)
+            } else {
+                let alt_reps = alt_ref.get_or_insert_with(|| Box::new(AltReps::new(slots)));
+                alt_reps.slots = slots;
+                Some(alt_reps.evict())
+            }
+        };
+
+        drop(evicted);
+    }
+
+    // Returns a shared copy of the value's current or cached data rep that matches
+    // the predicate, if any.  Unlike `promote`, this doesn't change the value.
+    fn find_rep(&self, matches: impl Fn(&Datum) -> bool) -> Option<Datum> {
+        let data = self.data();
+
+        if matches(&data) {
+            return Some(data);
+        }
+
+        let alt_ref = self.alt_reps.borrow();
+        alt_ref
+            .as_ref()?
+            .reps
+            .iter()
+            .find(|datum| matches(datum))
+            .map(Datum::share)
+    }
+
+    // If the value caches additional data reps and one of them matches the predicate,
+    // makes it the current data rep, if the shimmer policy allows, and returns a shared
+    // copy of it.
+    fn promote(&self, matches: impl Fn(&Datum) -> bool) -> Option<Datum> {
+        // FIRST, if the policy doesn't allow the current data rep to be replaced,
+        // just return the cached data rep.
+        if !self.may_replace() {
+            return self.find_rep(matches);
+        }
+
+        let datum = {
+            let mut alt_ref = self.alt_reps.borrow_mut();
+            let alt_reps = alt_ref.as_mut()?;
+            let i = alt_reps.reps.iter().position(matches)?;
+            alt_reps.reps.remove(i)
+        };
+
+        let out = datum.share();
+        self.set_data(datum);
+        Some(out)
+    }
+
+    // Returns a shared copy of the data rep.  External types' methods are only ever
+    // called on such a copy, never while the data rep is borrowed, so that they can
+    // safely access this same value.
+    fn data(&self) -> Datum {
+        self.data_rep.borrow().share()
+    }
+
+    // Returns the string rep, if it has been computed, and a shared copy of the data
+    // rep.  This allows a value to be copied without shimmering it.
+    pub(crate) fn reps(&self) -> (Option<&str>, Datum) {
+        (self.string_rep.get().map(|str| str.as_str()), self.data())
+    }
+
+    // Creates a value from its reps, as returned by `reps`.  The value must have a
+    // string rep, a data rep, or both.
+    pub(crate) fn from_reps(string_rep: Option<String>, data: Datum) -> MoltValue {
+        let value = MoltValue {
+            string_rep: OnceCell::new(),
+            data_rep: RefCell::new(data),
+            alt_reps: RefCell::new(None),
+            policy: Cell::new(ShimmerPolicy::Always),
+        };
+
+        if let Some(str) = string_rep {
+            let _ = value.string_rep.set(Rc::new(str));
+        }
+
+        value
+    }
+
+    // Returns the string rep for parsing as the given kind of data rep, counting the
+    // parse.
+    fn parse_str(&self, kind: &'static str) -> &str {
+        stats::count(kind, Event::Parse);
+        self.as_str()
+    }
+
+    // Saves the result of a conversion as the data rep, if the shimmer policy allows;
+    // otherwise, the result is simply dropped.
+    fn cache(&self, datum: Datum) {
+        if self.may_replace() {
+            self.set_data(datum);
+        }
+    }
+
+    // Does the shimmer policy allow the current data rep to be replaced?
+    fn may_replace(&self) -> bool {
+        match self.policy.get() {
+            ShimmerPolicy::Always => true,
+            ShimmerPolicy::Never => false,
+            ShimmerPolicy::KeepExpensive => !matches!(
+                *self.data_rep.borrow(),
+                Datum::List(_) | Datum::Dict(_) | Datum::Other(_)
+            ),
+            ShimmerPolicy::IfNone => matches!(*self.data_rep.borrow(), Datum::None),
+        }
+    }
+
+    // Replaces the data rep, caching the old one if the value caches additional data
+    // reps.  Replaced and evicted data reps are dropped only after the borrows are
+    // released.
+    fn set_data(&self, datum: Datum) {
+        let old = self.data_rep.replace(datum);
+
+        if !matches!(old, Datum::None) {
+            stats::count(old.kind_name(), Event::Replacement);
+        }
+
+        if self.alt_reps.borrow().is_none() {
+            return;
+        }
+
+        let current = self.data();
+        let evicted = self
+            .alt_reps
+            .borrow_mut()
+            .as_mut()
+            .map(|alt_reps| alt_reps.save(old, &current));
+
+        drop(evicted);
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Parsing helpers
+
+/// Is the character whitespace, from the point of view of TCL's number parsing?
+fn is_tcl_white(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0B' | '\x0C')
+}
+
+/// Removes the underscores from a string of digits in the given radix,
+/// returning None if the string is empty, contains a non-digit, or has an
+/// underscore anywhere but between two digits.
+fn strip_separators(digits: &str, radix: u32) -> Option<String> {
+    let mut out = String::new();
+    let mut prev_digit = false;
+    let mut chars = digits.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '_' {
+            let next_is_digit = chars.peek().is_some_and(|ch| ch.is_digit(radix));
+
+            if !prev_digit || !next_is_digit {
+                return None;
+            }
+            prev_digit = false;
+        } else if c.is_digit(radix) {
+            out.push(c);
+            prev_digit = true;
+        } else {
+            return None;
+        }
+    }
+
+    if out.is_empty() {
+        None
+    } else {
+        Some(out)
+    }
+}
+
+/// Verifies that a parsed float is representable, i.e., that a finite input
+/// didn't overflow to infinity.
+fn check_float(flt: MoltFloat) -> Result<MoltFloat, ResultCode> {
+    if flt.is_infinite() {
+        molt_err!("floating-point value too large to represent")
+    } else {
+        Ok(flt)
+    }
+}
+
+/// The error returned when an integer won't fit in a `MoltInt`.
+fn too_large() -> ResultCode {
+    molt_err_code("integer value too large to represent")
+}
+
+/// Creates a `ResultCode::Error` with the given message, for use with
+/// `ok_or_else` and friends.
+fn molt_err_code(msg: &str) -> ResultCode {
+    ResultCode::Error(MoltValue::from_string(msg.to_string()))
+}
+
+/// The maximum number of characters of an offending value to include in a
+/// conversion error message.
+const MAX_ERROR_VALUE_LEN: usize = 50;
+
+/// Creates the standard TCL conversion error, e.g., `expected integer but got "abc"`.
+/// Long values are truncated, with an ellipsis.
+fn expected(what: &str, arg: &str) -> ResultCode {
+    molt_err_code(&format!(
+        "expected {} but got \"{}\"",
+        what,
+        truncate(arg, MAX_ERROR_VALUE_LEN)
+    ))
+}
+
+/// Truncates a string to at most `max` characters for inclusion in an error message,
+/// adding an ellipsis if anything was removed.
+pub(crate) fn truncate(str: &str, max: usize) -> String {
+    match str.char_indices().nth(max) {
+        Some((i, _)) => format!("{}...", &str[..i]),
+        None => str.to_string(),
+    }
+}
+
+//-----------------------------------------------------------------------------
+// The MoltType Trait: the interface for external types.
 
-        // NEXT, we couldn't do it.
-        None
-    }
+/// A type that can be saved as the data rep of a `MoltValue`.  See
+/// [the module level documentation](index.html) for more.
+pub trait MoltType: Any + Debug + Sized {
+    /// The error returned when a string can't be parsed as a value of this type.
+    type Err: Display;
 
-    /// Tries to interpret the `MoltValue` as a value of type `T`, returning
-    /// a copy.
-    ///
-    /// This method returns `Option` rather than `Result` because it is up
-    /// to the caller to provide a meaningful error message.  It is normal
-    /// for externally defined types to wrap this function in a function
-    /// that does so.
-    ///
-    /// # Example
-    ///
-    /// TODO
-    pub fn as_copy<T: 'static>(&self) -> Option<T>
-    where
-        T: Display + Debug + FromStr + Copy,
-    {
-        let mut string_ref = self.string_rep.borrow_mut();
-        let mut data_ref = self.data_rep.borrow_mut();
+    /// Returns the type's name, e.g., "rgb", for use in error messages and
+    /// introspection.
+    fn type_name() -> &'static str;
 
-        // FIRST, if we have the desired type, return it.
-        if let Datum::Other(other) = &*data_ref {
-            // other is an &Rc<MoltAny>
-            let result = other.clone().downcast::<T>();
+    /// Produces the value's string rep, which must be parseable by `from_molt_str`.
+    fn to_molt_string(&self) -> String;
 
-            if result.is_ok() {
-                // Let's be sure we're really getting what we wanted.
-                let out: Rc<T> = result.unwrap();
-                return Some(*out);
-            }
-        }
+    /// Parses a value of this type from a string rep.
+    fn from_molt_str(str: &str) -> Result<Self, Self::Err>;
 
-        // NEXT, if we don't have a string_rep, get one.
-        if (*string_ref).is_none() {
-            *string_ref = Some(Rc::new(data_ref.to_string()));
-        }
+    /// Compares two values of this type.  Types with no natural ordering can
+    /// use the default, which returns `None`.
+    fn compare(&self, _other: &Self) -> Option<Ordering> {
+        None
+    }
 
-        // NEXT, can we parse it as a T?  If so, save it back to
-        // the data_rep, and return it.
-        if let Some(str) = &*string_ref {
-            if let Ok(tval) = str.parse::<T>() {
-                let tval = Rc::new(tval);
-                let out = tval.clone();
-                *data_ref = Datum::Other(Rc::new(tval));
-                return Some(*out);
-            }
-        }
+    /// Returns a hash of the value.  Types that can't be hashed can use the
+    /// default, which returns `None`.
+    fn hash_value(&self) -> Option<u64> {
+        None
+    }
 
-        // NEXT, we couldn't do it.
+    /// Returns a duplicate of the value, for use when a `MoltValue` with this data
+    /// rep is cloned.  Since data reps are immutable, the default returns `None`,
+    /// and the clones simply share the data rep.
+    fn duplicate(&self) -> Option<Self> {
         None
     }
 }
@@ -405,12 +1380,18 @@ impl MoltValue {
 //-----------------------------------------------------------------------------
 // The MoltAny Trait: a tool for handling external types.
 
-/// This trait allows us to except "other" types, and still compute their
-/// string rep on demand.
-trait MoltAny: Any + Display + Debug {
+/// This trait allows us to accept "other" types, and still compute their
+/// string rep on demand.  It is implemented for every `MoltType`, and gives
+/// access to the `MoltType` methods through a trait object.
+pub(crate) trait MoltAny: Any + Debug {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
     fn into_any(self: Box<Self>) -> Box<dyn Any>;
+    fn type_name(&self) -> &'static str;
+    fn to_molt_string(&self) -> String;
+    fn compare_any(&self, other: &dyn Any) -> Option<Ordering>;
+    fn hash_any(&self) -> Option<u64>;
+    fn duplicate_any(&self) -> Option<Rc<dyn MoltAny>>;
 }
 
 impl dyn MoltAny {
@@ -429,7 +1410,7 @@ impl dyn MoltAny {
     }
 }
 
-impl<T: Any + Display + Debug> MoltAny for T {
+impl<T: MoltType> MoltAny for T {
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -439,23 +1420,122 @@ impl<T: Any + Display + Debug> MoltAny for T {
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
     }
+    fn type_name(&self) -> &'static str {
+        T::type_name()
+    }
+    fn to_molt_string(&self) -> String {
+        MoltType::to_molt_string(self)
+    }
+    fn compare_any(&self, other: &dyn Any) -> Option<Ordering> {
+        other
+            .downcast_ref::<T>()
+            .and_then(|other| self.compare(other))
+    }
+    fn hash_any(&self) -> Option<u64> {
+        self.hash_value()
+    }
+    fn duplicate_any(&self) -> Option<Rc<dyn MoltAny>> {
+        self.duplicate().map(|dup| Rc::new(dup) as Rc<dyn MoltAny>)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// AltReps: a value's cache of additional data reps.
+
+// The additional data reps cached by a value, most recently used first.  See
+// `MoltValue::cache_reps`.
+#[derive(Debug)]
+struct AltReps {
+    // The maximum number of data reps to cache.
+    slots: usize,
+
+    // The cached data reps.
+    reps: Vec<Datum>,
+}
+
+impl AltReps {
+    fn new(slots: usize) -> Self {
+        Self {
+            slots,
+            reps: Vec::new(),
+        }
+    }
+
+    // Caches a data rep that has just been replaced by the current one.  Any cached
+    // rep of the same kind as either is replaced.  Returns the data reps that are no
+    // longer cached, so that the caller can drop them.
+    fn save(&mut self, datum: Datum, current: &Datum) -> Vec<Datum> {
+        let (mut dropped, kept): (Vec<Datum>, Vec<Datum>) = self
+            .reps
+            .drain(..)
+            .partition(|rep| rep.same_kind(&datum) || rep.same_kind(current));
+        self.reps = kept;
+
+        if let Datum::None = datum {
+            dropped.push(datum);
+        } else {
+            self.reps.insert(0, datum);
+        }
+
+        dropped.append(&mut self.evict());
+        dropped
+    }
+
+    // Evicts the least recently used data reps in excess of the number of slots,
+    // returning them.
+    fn evict(&mut self) -> Vec<Datum> {
+        let keep = self.slots.min(self.reps.len());
+        self.reps.split_off(keep)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Converters: direct conversions from one data rep to another.
+
+// A converter from a data rep, viewed as an `Any`, to an external type.  Converters
+// are shared by all threads.
+type Converter = Arc<dyn Fn(&dyn Any) -> Option<Rc<dyn MoltAny>> + Send + Sync>;
+
+// The registered converters, keyed by source and target type.
+static CONVERTERS: OnceLock<RwLock<HashMap<(TypeId, TypeId), Converter>>> = OnceLock::new();
+
+// Returns the table of registered converters.
+fn converters() -> &'static RwLock<HashMap<(TypeId, TypeId), Converter>> {
+    CONVERTERS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+// Looks up the converter from data rep `from` to external type `to`, if any.  The
+// converter is cloned out of the table so that it may itself convert values, or
+// register converters.
+fn converter(from: TypeId, to: TypeId) -> Option<Converter> {
+    converters()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&(from, to))
+        .cloned()
 }
 
 //-----------------------------------------------------------------------------
 // Datum enum: a sum type for the different kinds of data_reps.
 
 // The data representation for MoltValues.
-#[derive(Clone, Debug)]
-enum Datum {
+#[derive(Debug)]
+pub(crate) enum Datum {
     /// A Molt integer
     Int(MoltInt),
 
     /// A Molt float
     Flt(MoltFloat),
 
+    /// A Molt boolean
+    Bool(bool),
+
     /// A Molt List
     List(Rc<MoltList>),
 
+    /// A Molt Dictionary
+    Dict(Rc<MoltDict>),
+
     /// An external data type
     Other(Rc<dyn MoltAny>),
 
@@ -463,13 +1543,86 @@ enum Datum {
     None,
 }
 
+impl Datum {
+    // Returns a copy of the data rep that shares any reference-counted data.  Unlike
+    // `clone`, it never duplicates external data reps.
+    fn share(&self) -> Datum {
+        match self {
+            Datum::Int(int) => Datum::Int(*int),
+            Datum::Flt(flt) => Datum::Flt(*flt),
+            Datum::Bool(flag) => Datum::Bool(*flag),
+            Datum::List(list) => Datum::List(list.clone()),
+            Datum::Dict(dict) => Datum::Dict(dict.clone()),
+            Datum::Other(other) => Datum::Other(other.clone()),
+            Datum::None => Datum::None,
+        }
+    }
+
+    // Returns the name of this kind of data rep, for use in statistics: the
+    // external type's name, or the name of the standard type.
+    fn kind_name(&self) -> &'static str {
+        match self {
+            Datum::Int(_) => "int",
+            Datum::Flt(_) => "float",
+            Datum::Bool(_) => "bool",
+            Datum::List(_) => "list",
+            Datum::Dict(_) => "dict",
+            Datum::Other(other) => other.type_name(),
+            Datum::None => "none",
+        }
+    }
+
+    // Is this the same kind of data rep as the other?  External data reps are the
+    // same kind if they are of the same type.
+    fn same_kind(&self, other: &Datum) -> bool {
+        match (self, other) {
+            (Datum::Other(a), Datum::Other(b)) => a.as_any().type_id() == b.as_any().type_id(),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+
+    // Returns the data rep as an `Any`, for use by converters, or `None` if there is
+    // no data rep.
+    fn as_any(&self) -> Option<&dyn Any> {
+        match self {
+            Datum::Int(int) => Some(int as &dyn Any),
+            Datum::Flt(flt) => Some(flt as &dyn Any),
+            Datum::Bool(flag) => Some(flag as &dyn Any),
+            Datum::List(list) => Some(&**list as &dyn Any),
+            Datum::Dict(dict) => Some(&**dict as &dyn Any),
+            Datum::Other(other) => Some(other.as_any()),
+            Datum::None => None,
+        }
+    }
+}
+
+// External data reps are duplicated as their type requires; all other data reps
+// are simply shared or copied.
+impl Clone for Datum {
+    fn clone(&self) -> Self {
+        match self {
+            Datum::Int(int) => Datum::Int(*int),
+            Datum::Flt(flt) => Datum::Flt(*flt),
+            Datum::Bool(flag) => Datum::Bool(*flag),
+            Datum::List(list) => Datum::List(list.clone()),
+            Datum::Dict(dict) => Datum::Dict(dict.clone()),
+            Datum::Other(other) => {
+                Datum::Other(other.duplicate_any().unwrap_or_else(|| other.clone()))
+            }
+            Datum::None => Datum::None,
+        }
+    }
+}
+
 impl Display for Datum {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Datum::Int(int) => write!(f, "{}", int),
-            Datum::Flt(flt) => write!(f, "{}", flt),
-            Datum::List(_) => write!(f, "*FAKE LIST*"),
-            Datum::Other(other) => write!(f, "{}", other),
+            Datum::Flt(flt) => write!(f, "{}", MoltValue::fmt_float(*flt)),
+            Datum::Bool(flag) => write!(f, "{}", if *flag { 1 } else { 0 }),
+            Datum::List(list) => write!(f, "{}", list::list_to_string(list)),
+            Datum::Dict(dict) => write!(f, "{}", dict::dict_to_string(dict)),
+            Datum::Other(other) => write!(f, "{}", other.to_molt_string()),
             Datum::None => write!(f, ""),
         }
     }
@@ -490,12 +1643,43 @@ mod tests {
         assert_eq!(*val.to_string(), *val2.to_string());
     }
 
-    fn as_string() {
+    #[test]
+    fn as_str() {
         let val = MoltValue::from_string("abc".to_string());
-        assert_eq!(*val.as_string(), "abc".to_string());
+        assert_eq!(val.as_str(), "abc");
 
         let val2 = val.clone();
-        assert_eq!(*val.as_string(), *val2.to_string());
+        assert_eq!(val.as_str(), val2.to_string());
+
+        // The string rep is computed once, and then borrowed without copying.
+        let val = MoltValue::from_int(5);
+        let str = val.as_str();
+        assert_eq!(str, "5");
+        assert_eq!(val.as_int(), Ok(5));
+        assert!(std::ptr::eq(str, val.as_str()));
+    }
+
+    #[test]
+    fn result_code() {
+        // Errors are values, and can be inspected.
+        let val = MoltValue::from_string("abc".to_string());
+
+        match val.as_int() {
+            Err(ResultCode::Error(msg)) => {
+                assert_eq!(msg.to_string(), "expected integer but got \"abc\"")
+            }
+            _ => panic!("expected an error"),
+        }
+
+        // Result codes are compared by the string reps of their values.
+        let five = MoltValue::from_int(5);
+        assert_eq!(
+            ResultCode::Return(five.clone()),
+            ResultCode::Return(MoltValue::from_string("5".to_string()))
+        );
+        assert_ne!(ResultCode::Return(five.clone()), ResultCode::Error(five));
+        assert_eq!(ResultCode::Break, ResultCode::Break);
+        assert_ne!(ResultCode::Break, ResultCode::Continue);
     }
 
     #[test]
@@ -510,35 +1694,240 @@ mod tests {
         assert_eq!(val.as_int(), Ok(7));
         assert_eq!(val.as_float(), Ok(7.0));
 
-        // TODO: Note, 7.0 might not get converted to "7" long term.
-        // In Standard TCL, its string_rep would be "7.0".  Need to address
-        // MoltFloat formatting/parsing.
+        // As in Standard TCL, a float's string rep is never an integer.
         let val = MoltValue::from_float(7.0);
-        assert_eq!(*val.to_string(), "7".to_string());
-        assert_eq!(val.as_int(), Ok(7));
+        assert_eq!(*val.to_string(), "7.0".to_string());
+        assert_eq!(
+            val.as_int(),
+            molt_err!("expected integer but got \"{}\"", val)
+        );
         assert_eq!(val.as_float(), Ok(7.0));
 
+        let val = MoltValue::from_string(" 0x1F ".to_string());
+        assert_eq!(val.as_int(), Ok(31));
+        assert_eq!(*val.to_string(), " 0x1F ".to_string());
+
         let val = MoltValue::from_string("abc".to_string());
-        assert_eq!(val.as_int(), Err("Not an integer".to_string()));
+        assert_eq!(
+            val.as_int(),
+            molt_err!("expected integer but got \"{}\"", val)
+        );
+    }
+
+    #[test]
+    fn get_int() {
+        assert_eq!(MoltValue::get_int("0"), Ok(0));
+        assert_eq!(MoltValue::get_int("42"), Ok(42));
+        assert_eq!(MoltValue::get_int("  42  "), Ok(42));
+        assert_eq!(MoltValue::get_int("\t42\n"), Ok(42));
+        assert_eq!(MoltValue::get_int("+7"), Ok(7));
+        assert_eq!(MoltValue::get_int("-7"), Ok(-7));
+        assert_eq!(MoltValue::get_int("007"), Ok(7));
+        assert_eq!(MoltValue::get_int("0x1F"), Ok(31));
+        assert_eq!(MoltValue::get_int("0X1f"), Ok(31));
+        assert_eq!(MoltValue::get_int("-0x10"), Ok(-16));
+        assert_eq!(MoltValue::get_int("0o17"), Ok(15));
+        assert_eq!(MoltValue::get_int("0b101"), Ok(5));
+        assert_eq!(MoltValue::get_int("1_000_000"), Ok(1_000_000));
+        assert_eq!(MoltValue::get_int("0xFF_FF"), Ok(0xFFFF));
+        assert_eq!(MoltValue::get_int("9223372036854775807"), Ok(MoltInt::MAX));
+        assert_eq!(MoltValue::get_int("-9223372036854775808"), Ok(MoltInt::MIN));
+        assert_eq!(MoltValue::get_int("-0x8000000000000000"), Ok(MoltInt::MIN));
+
+        for arg in &[
+            "", "  ", "-", "0x", "abc", "1 2", "0b102", "0o8", "1.5", "_1", "1_", "1__0", "0x_1",
+            "--1",
+        ] {
+            assert_eq!(
+                MoltValue::get_int(arg),
+                molt_err!("expected integer but got \"{}\"", arg)
+            );
+        }
+
+        let too_large = molt_err!("integer value too large to represent");
+        assert_eq!(MoltValue::get_int("9223372036854775808"), too_large);
+        assert_eq!(MoltValue::get_int("-9223372036854775809"), too_large);
+        assert_eq!(MoltValue::get_int("0x1_0000_0000_0000_0000"), too_large);
+    }
+
+    #[test]
+    fn fmt_float() {
+        assert_eq!(MoltValue::fmt_float(0.0), "0.0");
+        assert_eq!(MoltValue::fmt_float(-0.0), "-0.0");
+        assert_eq!(MoltValue::fmt_float(7.0), "7.0");
+        assert_eq!(MoltValue::fmt_float(-7.0), "-7.0");
+        assert_eq!(MoltValue::fmt_float(12.5), "12.5");
+        assert_eq!(MoltValue::fmt_float(0.1), "0.1");
+        assert_eq!(MoltValue::fmt_float(1.0 / 3.0), "0.3333333333333333");
+        assert_eq!(MoltValue::fmt_float(100.0), "100.0");
+        assert_eq!(MoltValue::fmt_float(123.456), "123.456");
+        assert_eq!(MoltValue::fmt_float(0.0001), "0.0001");
+        assert_eq!(MoltValue::fmt_float(0.00012), "0.00012");
+        assert_eq!(MoltValue::fmt_float(0.00001), "1e-5");
+        assert_eq!(MoltValue::fmt_float(1.5e-10), "1.5e-10");
+        assert_eq!(MoltValue::fmt_float(1e16), "10000000000000000.0");
+        assert_eq!(MoltValue::fmt_float(1e17), "1e+17");
+        assert_eq!(MoltValue::fmt_float(-2.5e100), "-2.5e+100");
+        assert_eq!(MoltValue::fmt_float(MoltFloat::INFINITY), "Inf");
+        assert_eq!(MoltValue::fmt_float(MoltFloat::NEG_INFINITY), "-Inf");
+        assert_eq!(MoltValue::fmt_float(MoltFloat::NAN), "NaN");
+
+        // Round-trips
+        for flt in &[0.1, 1.0 / 3.0, 1e17, 1e-5, 6.02214076e23, MoltFloat::MAX] {
+            let str = MoltValue::fmt_float(*flt);
+            assert_eq!(str.parse::<MoltFloat>(), Ok(*flt));
+        }
+    }
+
+    #[test]
+    fn conversion_errors() {
+        // Long values are truncated in error messages.
+        let long = "x".repeat(100);
+        let val = MoltValue::from_string(long);
+        assert_eq!(
+            val.as_int(),
+            molt_err!("expected integer but got \"{}...\"", "x".repeat(50))
+        );
+        assert_eq!(
+            val.as_float(),
+            molt_err!(
+                "expected floating-point number but got \"{}...\"",
+                "x".repeat(50)
+            )
+        );
+        assert_eq!(
+            val.as_bool(),
+            molt_err!("expected boolean value but got \"{}...\"", "x".repeat(50))
+        );
+
+        // Truncation counts characters, not bytes.
+        assert_eq!(truncate("ééé", 2), "éé...");
+        assert_eq!(truncate("ééé", 3), "ééé");
+    }
+
+    #[test]
+    fn get_float() {
+        assert_eq!(MoltValue::get_float("0"), Ok(0.0));
+        assert_eq!(MoltValue::get_float("12.5"), Ok(12.5));
+        assert_eq!(MoltValue::get_float("  12.5\n"), Ok(12.5));
+        assert_eq!(MoltValue::get_float("+12.5"), Ok(12.5));
+        assert_eq!(MoltValue::get_float("-12.5"), Ok(-12.5));
+        assert_eq!(MoltValue::get_float("1."), Ok(1.0));
+        assert_eq!(MoltValue::get_float(".5"), Ok(0.5));
+        assert_eq!(MoltValue::get_float("-.5"), Ok(-0.5));
+        assert_eq!(MoltValue::get_float("1e3"), Ok(1000.0));
+        assert_eq!(MoltValue::get_float("1.5E-3"), Ok(0.0015));
+        assert_eq!(MoltValue::get_float("1.e+2"), Ok(100.0));
+        assert_eq!(MoltValue::get_float("1_000.5"), Ok(1000.5));
+        assert_eq!(MoltValue::get_float("0x10"), Ok(16.0));
+        assert_eq!(MoltValue::get_float("-0x10"), Ok(-16.0));
+        assert_eq!(MoltValue::get_float("0o17"), Ok(15.0));
+        assert_eq!(MoltValue::get_float("0b101"), Ok(5.0));
+        assert_eq!(
+            MoltValue::get_float("99999999999999999999"),
+            Ok(99999999999999999999.0)
+        );
+        assert_eq!(MoltValue::get_float("Inf"), Ok(MoltFloat::INFINITY));
+        assert_eq!(MoltValue::get_float("-inf"), Ok(MoltFloat::NEG_INFINITY));
+        assert_eq!(MoltValue::get_float("Infinity"), Ok(MoltFloat::INFINITY));
+        assert_eq!(MoltValue::get_float(" +INF "), Ok(MoltFloat::INFINITY));
+        assert!(MoltValue::get_float("NaN").unwrap().is_nan());
+        assert!(MoltValue::get_float("nan").unwrap().is_nan());
+
+        for arg in &[
+            "", "abc", ".", "-", "e5", "1e", "1e+", "1.2.3", "1 2", "0x", "0x1.8", "infinite",
+            "--1", "1_", "1e5_0", "1e_5", "1e-5_0", "-nan", "+NaN", " -NaN ",
+        ] {
+            assert_eq!(
+                MoltValue::get_float(arg),
+                molt_err!("expected floating-point number but got \"{}\"", arg)
+            );
+        }
+
+        let too_large = molt_err!("floating-point value too large to represent");
+        assert_eq!(MoltValue::get_float("1e999"), too_large);
+        assert_eq!(MoltValue::get_float("-1e999"), too_large);
+        assert_eq!(MoltValue::get_float("1e-999"), Ok(0.0));
     }
 
     #[test]
     fn from_as_float() {
         let val = MoltValue::from_float(12.5);
         assert_eq!(*val.to_string(), "12.5".to_string());
-        assert_eq!(val.as_int(), Err("Not an integer".to_string()));
+        assert_eq!(
+            val.as_int(),
+            molt_err!("expected integer but got \"{}\"", val)
+        );
         assert_eq!(val.as_float(), Ok(12.5));
 
         let val = MoltValue::from_string("7.8".to_string());
         assert_eq!(*val.to_string(), "7.8".to_string());
-        assert_eq!(val.as_int(), Err("Not an integer".to_string()));
+        assert_eq!(
+            val.as_int(),
+            molt_err!("expected integer but got \"{}\"", val)
+        );
         assert_eq!(val.as_float(), Ok(7.8));
 
         let val = MoltValue::from_int(5);
         assert_eq!(val.as_float(), Ok(5.0));
 
+        let val = MoltValue::from_string(" 0x10 ".to_string());
+        assert_eq!(val.as_float(), Ok(16.0));
+
         let val = MoltValue::from_string("abc".to_string());
-        assert_eq!(val.as_float(), Err("Not a float".to_string()));
+        assert_eq!(
+            val.as_float(),
+            molt_err!("expected floating-point number but got \"abc\"")
+        );
+    }
+
+    #[test]
+    fn from_as_bool() {
+        let val = MoltValue::from_bool(true);
+        assert_eq!(*val.to_string(), "1".to_string());
+        assert_eq!(val.as_bool(), Ok(true));
+        assert_eq!(val.as_int(), Ok(1));
+
+        let val = MoltValue::from_bool(false);
+        assert_eq!(*val.to_string(), "0".to_string());
+        assert_eq!(val.as_bool(), Ok(false));
+
+        let val = MoltValue::from_string("yes".to_string());
+        assert_eq!(val.as_bool(), Ok(true));
+        assert_eq!(*val.to_string(), "yes".to_string());
+
+        let val = MoltValue::from_int(5);
+        assert_eq!(val.as_bool(), Ok(true));
+
+        let val = MoltValue::from_string("maybe".to_string());
+        assert_eq!(
+            val.as_bool(),
+            molt_err!("expected boolean value but got \"maybe\"")
+        );
+    }
+
+    #[test]
+    fn get_bool() {
+        for word in &[
+            "1", "-1", "5", "0x10", "true", "TRUE", "t", "Tr", "yes", "y", "on", "ON",
+        ] {
+            assert_eq!(MoltValue::get_bool(word), Ok(true), "word: {}", word);
+        }
+
+        for word in &[
+            "0", "0x0", "false", "False", "f", "fal", "no", "n", "off", "of",
+        ] {
+            assert_eq!(MoltValue::get_bool(word), Ok(false), "word: {}", word);
+        }
+
+        for word in &[
+            "", "o", "truex", "nope", "onn", "1.5", " true", " 0 ", "1\n", "\t0", "maybe",
+        ] {
+            assert_eq!(
+                MoltValue::get_bool(word),
+                molt_err!("expected boolean value but got \"{}\"", word)
+            );
+        }
     }
 
     #[test]
@@ -557,6 +1946,88 @@ mod tests {
             assert_eq!(rclist[0].to_string(), a.to_string());
             assert_eq!(rclist[1].to_string(), b.to_string());
         }
+
+        // Parse it from a string.
+        let listval = MoltValue::from_string("a {b c} \"d e\"".to_string());
+        let rclist = listval.as_list().unwrap();
+        assert_eq!(rclist.len(), 3);
+        assert_eq!(rclist[0].to_string(), "a".to_string());
+        assert_eq!(rclist[1].to_string(), "b c".to_string());
+        assert_eq!(rclist[2].to_string(), "d e".to_string());
+
+        // The parsed list is cached.
+        let rclist2 = listval.as_list().unwrap();
+        assert!(Rc::ptr_eq(&rclist, &rclist2));
+
+        // The canonical string rep round-trips.
+        let listval = MoltValue::from_list(vec![
+            MoltValue::from_string("a b".to_string()),
+            MoltValue::from_string("".to_string()),
+            MoltValue::from_int(7),
+        ]);
+        assert_eq!(listval.to_string(), "{a b} {} 7".to_string());
+        let listval2 = MoltValue::from_string(listval.to_string());
+        let rclist = listval2.as_list().unwrap();
+        assert_eq!(rclist.len(), 3);
+        assert_eq!(rclist[0].to_string(), "a b".to_string());
+        assert_eq!(rclist[1].to_string(), "".to_string());
+        assert_eq!(rclist[2].as_int(), Ok(7));
+
+        // Other data reps can shimmer to lists.
+        let listval = MoltValue::from_int(5);
+        let rclist = listval.as_list().unwrap();
+        assert_eq!(rclist.len(), 1);
+        assert_eq!(rclist[0].as_int(), Ok(5));
+
+        let listval = MoltValue::from_string("a {b c".to_string());
+        assert_eq!(
+            listval.as_list().map(|_| ()),
+            molt_err!("unmatched open brace in list")
+        );
+    }
+
+    #[test]
+    fn from_as_dict() {
+        let mut dict = MoltDict::new();
+        dict.insert(
+            MoltValue::from_string("a".to_string()),
+            MoltValue::from_int(1),
+        );
+        dict.insert(
+            MoltValue::from_string("b c".to_string()),
+            MoltValue::from_float(2.5),
+        );
+        let dictval = MoltValue::from_dict(dict);
+        assert_eq!(dictval.to_string(), "a 1 {b c} 2.5".to_string());
+
+        let rcdict = dictval.as_dict().unwrap();
+        assert_eq!(rcdict.len(), 2);
+        assert_eq!(rcdict.get("a").unwrap().as_int(), Ok(1));
+
+        // Parse it from a string; the last duplicate key wins.
+        let dictval = MoltValue::from_string("a 1 b 2 a 3".to_string());
+        let rcdict = dictval.as_dict().unwrap();
+        assert_eq!(rcdict.len(), 2);
+        assert_eq!(rcdict.get("a").unwrap().as_int(), Ok(3));
+        assert_eq!(dictval.to_string(), "a 1 b 2 a 3".to_string());
+
+        // The parsed dict is cached.
+        let rcdict2 = dictval.as_dict().unwrap();
+        assert!(Rc::ptr_eq(&rcdict, &rcdict2));
+
+        // A list with an even number of elements is a dict, and vice versa.
+        let listval = MoltValue::from_list(vec![
+            MoltValue::from_string("x".to_string()),
+            MoltValue::from_int(1),
+        ]);
+        assert_eq!(listval.as_dict().unwrap().get("x").unwrap().as_int(), Ok(1));
+        assert_eq!(listval.as_list().unwrap().len(), 2);
+
+        let dictval = MoltValue::from_string("a 1 b".to_string());
+        assert_eq!(
+            dictval.as_dict().map(|_| ()),
+            molt_err!("missing value to go with key")
+        );
     }
 
     #[test]
@@ -564,23 +2035,638 @@ mod tests {
         // Give a Flavor, get an Rc<Flavor> back.
         let myval = MoltValue::from_other(Flavor::SALTY);
         let result = myval.as_other::<Flavor>();
-        assert!(result.is_some());
+        assert!(result.is_ok());
         let out = result.unwrap();
         assert_eq!(*out, Flavor::SALTY);
 
         // Give a String, get an Rc<Flavor> back.
         let myval = MoltValue::from_string("sweet".to_string());
         let result = myval.as_other::<Flavor>();
-        assert!(result.is_some());
+        assert!(result.is_ok());
         let out = result.unwrap();
         assert_eq!(*out, Flavor::SWEET);
 
         // Flavor is Copy, so get a Flavor back
         let myval = MoltValue::from_other(Flavor::SALTY);
         let result = myval.as_copy::<Flavor>();
-        assert!(result.is_some());
+        assert!(result.is_ok());
         let out = result.unwrap();
         assert_eq!(out, Flavor::SALTY);
+
+        // Errors come from the type's parser.
+        let myval = MoltValue::from_string("sour".to_string());
+        assert_eq!(
+            myval.as_other::<Flavor>(),
+            molt_err!("expected flavor but got \"sour\"")
+        );
+        assert_eq!(Flavor::from_molt(&myval), molt_err!("Not a flavor string"));
+    }
+
+    #[test]
+    fn as_other_caches() {
+        // The first call parses the string rep; later calls use the cached data rep.
+        let myval = MoltValue::from_string("7".to_string());
+        let before = Counted::parses();
+
+        let first = myval.as_other::<Counted>().unwrap();
+        assert_eq!(Counted::parses(), before + 1);
+
+        let second = myval.as_other::<Counted>().unwrap();
+        assert_eq!(Counted::parses(), before + 1);
+        assert!(Rc::ptr_eq(&first, &second));
+        assert_eq!(second.0, 7);
+
+        // The same is true for as_copy.
+        let myval = MoltValue::from_string("sweet".to_string());
+        assert_eq!(myval.as_copy::<Flavor>(), Ok(Flavor::SWEET));
+        let cached = myval.as_other::<Flavor>().unwrap();
+        assert_eq!(myval.as_copy::<Flavor>(), Ok(Flavor::SWEET));
+        assert!(Rc::ptr_eq(&cached, &myval.as_other::<Flavor>().unwrap()));
+
+        // A value created from a T is never parsed.
+        let myval = MoltValue::from_other(Counted(3));
+        let before = Counted::parses();
+        assert_eq!(myval.as_other::<Counted>().unwrap().0, 3);
+        assert_eq!(Counted::parses(), before);
+    }
+
+    #[test]
+    fn cache_reps() {
+        // By default, alternating data reps reparses the string rep each time.
+        let myval = MoltValue::from_string("5".to_string());
+        let before = Counted::parses();
+        myval.as_other::<Counted>().unwrap();
+        myval.as_list().unwrap();
+        myval.as_other::<Counted>().unwrap();
+        assert_eq!(Counted::parses(), before + 2);
+
+        // With a cache, the replaced data rep is kept and reused.
+        let myval = MoltValue::from_string("5".to_string());
+        myval.cache_reps(2);
+        let before = Counted::parses();
+        let counted = myval.as_other::<Counted>().unwrap();
+        let list = myval.as_list().unwrap();
+        assert!(Rc::ptr_eq(&counted, &myval.as_other::<Counted>().unwrap()));
+        assert!(Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+        assert_eq!(myval.as_int(), Ok(5));
+        assert_eq!(myval.as_other::<Counted>().unwrap().0, 5);
+        assert_eq!(Counted::parses(), before + 1);
+
+        // The least recently used data rep is evicted: here, the list.
+        myval.as_float().unwrap();
+        assert!(!Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+
+        // Each kind of data rep is cached at most once.
+        let myval = MoltValue::from_int(1);
+        myval.cache_reps(3);
+        myval.as_list().unwrap();
+        myval.as_int().unwrap();
+        myval.as_list().unwrap();
+        assert_eq!(myval.alt_reps.borrow().as_ref().unwrap().reps.len(), 1);
+
+        // Reducing the number of slots evicts the excess; 0 evicts them all.
+        let myval = MoltValue::from_string("1".to_string());
+        myval.cache_reps(3);
+        myval.as_int().unwrap();
+        myval.as_list().unwrap();
+        myval.as_dict().unwrap_err();
+        myval.as_bool().unwrap();
+        assert_eq!(myval.alt_reps.borrow().as_ref().unwrap().reps.len(), 2);
+        myval.cache_reps(1);
+        assert!(matches!(
+            myval.alt_reps.borrow().as_ref().unwrap().reps[..],
+            [Datum::List(_)]
+        ));
+        myval.cache_reps(0);
+        assert!(myval.alt_reps.borrow().is_none());
+
+        // Clones have the same number of slots, but no cached data reps.
+        let myval = MoltValue::from_int(1);
+        myval.cache_reps(2);
+        myval.as_list().unwrap();
+        let clone = myval.clone();
+        assert_eq!(clone.alt_reps.borrow().as_ref().unwrap().slots, 2);
+        assert!(clone.alt_reps.borrow().as_ref().unwrap().reps.is_empty());
+    }
+
+    #[test]
+    fn probes() {
+        // Probing a list doesn't replace its data rep.
+        let myval = MoltValue::from_string("1 2 3".to_string());
+        let list = myval.as_list().unwrap();
+        assert!(!myval.is_int());
+        assert!(!myval.is_float());
+        assert!(!myval.is_bool());
+        assert!(myval.is_list());
+        assert!(!myval.is_dict());
+        assert!(!myval.can_be::<Flavor>());
+        assert_eq!(
+            myval.try_int_no_cache(),
+            molt_err!("expected integer but got \"1 2 3\"")
+        );
+        assert!(Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+
+        // Successful probes don't replace it either.
+        let myval = MoltValue::from_string("0x10".to_string());
+        let list = myval.as_list().unwrap();
+        assert!(myval.is_int());
+        assert!(myval.is_float());
+        assert!(myval.is_bool());
+        assert!(!myval.is_dict());
+        assert_eq!(myval.try_int_no_cache(), Ok(16));
+        assert!(Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+
+        // Nor does probing an external type.
+        let myval = MoltValue::from_other(Counted(5));
+        let counted = myval.as_other::<Counted>().unwrap();
+        let before = Counted::parses();
+        assert!(myval.can_be::<Counted>());
+        assert!(!myval.can_be::<Flavor>());
+        assert_eq!(myval.try_int_no_cache(), Ok(5));
+        assert!(myval.is_list());
+        assert_eq!(Counted::parses(), before);
+        assert!(Rc::ptr_eq(&counted, &myval.as_other::<Counted>().unwrap()));
+
+        // Probing parses the string rep each time, as the result isn't cached.
+        let myval = MoltValue::from_string("7".to_string());
+        let before = Counted::parses();
+        assert!(myval.can_be::<Counted>());
+        assert!(myval.can_be::<Counted>());
+        assert_eq!(Counted::parses(), before + 2);
+        assert_eq!(myval.other_type_name(), None);
+
+        // Cached data reps are found without parsing.
+        let myval = MoltValue::from_int(3);
+        myval.cache_reps(1);
+        myval.as_list().unwrap();
+        assert_eq!(myval.try_int_no_cache(), Ok(3));
+        assert!(matches!(*myval.data_rep.borrow(), Datum::List(_)));
+    }
+
+    #[test]
+    fn shimmer_policy() {
+        // The default policy replaces the data rep.
+        let myval = MoltValue::from_string("5".to_string());
+        assert_eq!(myval.shimmer_policy(), ShimmerPolicy::Always);
+        let list = myval.as_list().unwrap();
+        assert_eq!(myval.as_int(), Ok(5));
+        assert!(!Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+
+        // Never: convert, but don't cache.
+        let myval = MoltValue::from_string("5".to_string());
+        let list = myval.as_list().unwrap();
+        myval.set_shimmer_policy(ShimmerPolicy::Never);
+        assert_eq!(myval.as_int(), Ok(5));
+        assert_eq!(myval.as_other::<Counted>().unwrap().0, 5);
+        assert!(Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+
+        // KeepExpensive: lists, dicts, and external types are kept; others aren't.
+        let myval = MoltValue::from_int(5);
+        myval.set_shimmer_policy(ShimmerPolicy::KeepExpensive);
+        let list = myval.as_list().unwrap();
+        assert_eq!(myval.as_float(), Ok(5.0));
+        assert!(Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+
+        let myval = MoltValue::from_other(Counted(5));
+        myval.set_shimmer_policy(ShimmerPolicy::KeepExpensive);
+        assert_eq!(myval.as_list().unwrap().len(), 1);
+        assert_eq!(myval.other_type_name(), Some("counted"));
+
+        // IfNone: only a value with no data rep is converted.
+        let myval = MoltValue::from_string("5".to_string());
+        myval.set_shimmer_policy(ShimmerPolicy::IfNone);
+        assert_eq!(myval.as_int(), Ok(5));
+        assert_eq!(myval.as_list().unwrap().len(), 1);
+        assert!(matches!(*myval.data_rep.borrow(), Datum::Int(5)));
+
+        // with_policy applies a policy to a single conversion.
+        let myval = MoltValue::from_string("5".to_string());
+        let list = myval.as_list().unwrap();
+        assert_eq!(
+            myval.with_policy(ShimmerPolicy::Never, |v| v.as_int()),
+            Ok(5)
+        );
+        assert_eq!(myval.shimmer_policy(), ShimmerPolicy::Always);
+        assert!(Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+
+        // The policy is restored even if the function panics.
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            myval.with_policy(ShimmerPolicy::Never, |_| panic!("oops"))
+        }));
+        assert!(result.is_err());
+        assert_eq!(myval.shimmer_policy(), ShimmerPolicy::Always);
+
+        // Clones have the same policy.
+        myval.set_shimmer_policy(ShimmerPolicy::IfNone);
+        assert_eq!(myval.clone().shimmer_policy(), ShimmerPolicy::IfNone);
+
+        // A policy that forbids replacing the data rep also keeps cached data reps
+        // from replacing it.
+        let myval = MoltValue::from_int(5);
+        myval.cache_reps(1);
+        let list = myval.as_list().unwrap();
+        myval.set_shimmer_policy(ShimmerPolicy::Never);
+        assert_eq!(myval.as_int(), Ok(5));
+        assert!(Rc::ptr_eq(&list, &myval.as_list().unwrap()));
+        assert!(matches!(*myval.data_rep.borrow(), Datum::List(_)));
+    }
+
+    #[test]
+    fn introspection() {
+        let myval = MoltValue::from_string("a b c".to_string());
+        assert!(myval.has_string_rep());
+        assert_eq!(myval.rep_kind(), RepKind::None);
+        assert_eq!(myval.string_rep_strong_count(), Some(1));
+        assert_eq!(myval.data_rep_strong_count(), None);
+        assert_eq!(
+            myval.describe(),
+            "value is a pure string, string representation \"a b c\""
+        );
+
+        // Clones share the string rep and data rep.
+        let list = myval.as_list().unwrap();
+        let clone = myval.clone();
+        assert_eq!(myval.rep_kind(), RepKind::List);
+        assert_eq!(myval.string_rep_strong_count(), Some(2));
+        assert_eq!(myval.data_rep_strong_count(), Some(3));
+        drop(list);
+        drop(clone);
+        assert_eq!(
+            myval.describe(),
+            "value is a list with a refcount of 1, string representation \"a b c\""
+        );
+
+        let myval = MoltValue::from_int(5);
+        assert!(!myval.has_string_rep());
+        assert_eq!(myval.rep_kind(), RepKind::Int);
+        assert_eq!(myval.string_rep_strong_count(), None);
+        assert_eq!(myval.describe(), "value is a int, no string representation");
+        assert_eq!(
+            format!("{:?}", myval),
+            "MoltValue { string_rep: <none>, data_rep: Int(5) }"
+        );
+        myval.as_str();
+        assert_eq!(
+            format!("{:?}", myval),
+            "MoltValue { string_rep: \"5\", data_rep: Int(5) }"
+        );
+
+        let myval = MoltValue::from_other(Flavor::SALTY);
+        assert_eq!(myval.rep_kind(), RepKind::Other("flavor"));
+        assert_eq!(myval.rep_kind().to_string(), "flavor");
+        assert_eq!(
+            myval.describe(),
+            "value is a flavor with a refcount of 1, no string representation"
+        );
+
+        // Long string reps are truncated.
+        let myval = MoltValue::from_string("x".repeat(60));
+        assert!(myval
+            .describe()
+            .ends_with(&format!("\"{}...\"", "x".repeat(50))));
+
+        // Cached data reps are shown by Debug.
+        let myval = MoltValue::from_float(1.5);
+        myval.cache_reps(1);
+        myval.as_int().unwrap_err();
+        myval.as_list().unwrap();
+        assert!(format!("{:?}", myval).ends_with("alt_reps: [Flt(1.5)] }"));
+        assert_eq!(
+            [RepKind::Float, RepKind::Bool, RepKind::Dict, RepKind::None]
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect::<Vec<_>>(),
+            vec!["float", "bool", "dict", "none"]
+        );
+    }
+
+    #[test]
+    fn molt_type_hooks() {
+        // compare_other uses MoltType::compare, if both values have the same type.
+        let a = MoltValue::from_other(Counted(1));
+        let b = MoltValue::from_string("2".to_string());
+        assert_eq!(a.compare_other(&b), None);
+        b.as_other::<Counted>().unwrap();
+        assert_eq!(a.compare_other(&b), Some(Ordering::Less));
+        assert_eq!(b.compare_other(&a), Some(Ordering::Greater));
+
+        let c = MoltValue::from_other(Flavor::SALTY);
+        assert_eq!(a.compare_other(&c), None);
+        assert_eq!(c.compare_other(&c), None);
+
+        // other_type_name uses MoltType::type_name.
+        assert_eq!(a.other_type_name(), Some("counted"));
+        assert_eq!(c.other_type_name(), Some("flavor"));
+        assert_eq!(MoltValue::from_int(1).other_type_name(), None);
+
+        // hash_other uses MoltType::hash_value.
+        assert_eq!(a.hash_other(), Some(1));
+        assert_eq!(c.hash_other(), None);
+        assert_eq!(MoltValue::from_int(1).hash_other(), None);
+
+        // Cloning a value uses MoltType::duplicate, if defined.
+        let before = Counted::duplicates();
+        let a2 = a.clone();
+        assert_eq!(Counted::duplicates(), before + 1);
+        assert!(!Rc::ptr_eq(
+            &a.as_other::<Counted>().unwrap(),
+            &a2.as_other::<Counted>().unwrap()
+        ));
+
+        let c2 = c.clone();
+        assert!(Rc::ptr_eq(
+            &c.as_other::<Flavor>().unwrap(),
+            &c2.as_other::<Flavor>().unwrap()
+        ));
+    }
+
+    #[test]
+    fn converters() {
+        // Types used only by this test, so that its converters affect no other test.
+        #[derive(Debug)]
+        struct Celsius(i64);
+
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Small(u8);
+
+        thread_local! {
+            static CELSIUS_PARSES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        }
+
+        fn parses() -> usize {
+            CELSIUS_PARSES.with(|c| c.get())
+        }
+
+        impl MoltType for Celsius {
+            type Err = String;
+
+            fn type_name() -> &'static str {
+                "celsius"
+            }
+
+            fn to_molt_string(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+                CELSIUS_PARSES.with(|c| c.set(c.get() + 1));
+                str.parse()
+                    .map(Celsius)
+                    .map_err(|_| format!("expected celsius but got \"{}\"", str))
+            }
+        }
+
+        impl MoltType for Small {
+            type Err = String;
+
+            fn type_name() -> &'static str {
+                "small"
+            }
+
+            fn to_molt_string(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+                str.parse()
+                    .map(Small)
+                    .map_err(|_| format!("expected small but got \"{}\"", str))
+            }
+        }
+
+        // Each converter produces what parsing the source's string rep would.
+        MoltValue::register_converter::<MoltInt, Celsius>(|int| Some(Celsius(*int)));
+        MoltValue::register_converter::<Celsius, Small>(|c| {
+            std::convert::TryFrom::try_from(c.0).ok().map(Small)
+        });
+
+        // An int converts directly, without parsing; its string rep is retained.
+        let myval = MoltValue::from_int(5);
+        let before = parses();
+        assert_eq!(myval.as_other::<Celsius>().unwrap().0, 5);
+        assert_eq!(parses(), before);
+        assert_eq!(myval.string_rep.get().map(|s| s.as_str()), Some("5"));
+
+        // The converted value is cached.
+        let first = myval.as_other::<Celsius>().unwrap();
+        assert!(Rc::ptr_eq(&first, &myval.as_other::<Celsius>().unwrap()));
+        assert_eq!(myval.to_string(), "5");
+
+        // External types convert directly, too, and keep their string reps.
+        let myval = MoltValue::from_other(Celsius(1));
+        assert_eq!(myval.as_copy::<Small>(), Ok(Small(1)));
+        assert_eq!(myval.other_type_name(), Some("small"));
+        assert_eq!(myval.to_string(), "1");
+
+        // If the converter declines, the string rep is parsed as usual.
+        let myval = MoltValue::from_other(Celsius(300));
+        assert_eq!(
+            myval.as_copy::<Small>(),
+            molt_err!("expected small but got \"300\"")
+        );
+
+        // Data reps without a converter are parsed as usual.
+        let myval = MoltValue::from_float(5.0);
+        let before = parses();
+        assert!(myval.as_other::<Celsius>().is_err());
+        assert_eq!(parses(), before + 1);
+
+        // Converters are used on every thread, not just the one that registered them.
+        std::thread::spawn(|| {
+            let myval = MoltValue::from_int(8);
+            assert_eq!(myval.as_other::<Celsius>().unwrap().0, 8);
+            assert_eq!(parses(), 0);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn reentrant_types() {
+        // Producing the string rep.
+        let myval = Nosy::target(MoltValue::from_other(Nosy(3)));
+        assert_eq!(myval.as_str(), "3");
+        assert_eq!(myval.as_int(), Ok(3));
+
+        // Parsing the string rep.
+        let myval = Nosy::target(MoltValue::from_string("4".to_string()));
+        assert_eq!(myval.as_other::<Nosy>().unwrap().0, 4);
+        assert_eq!(myval.other_type_name(), Some("nosy"));
+
+        // Converting the data rep, to a type used only by this test.
+        #[derive(Debug)]
+        struct Poked(i64);
+
+        impl MoltType for Poked {
+            type Err = String;
+
+            fn type_name() -> &'static str {
+                "poked"
+            }
+
+            fn to_molt_string(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+                str.parse().map(Poked).map_err(|_| "not poked".to_string())
+            }
+        }
+
+        MoltValue::register_converter::<Nosy, Poked>(|nosy| {
+            Nosy::poke();
+            Some(Poked(nosy.0))
+        });
+        let myval = Nosy::target(MoltValue::from_other(Nosy(6)));
+        assert_eq!(myval.as_other::<Poked>().unwrap().0, 6);
+        assert_eq!(myval.as_str(), "6");
+
+        // Producing the string rep of a list that contains the value.
+        let myval = Nosy::target(MoltValue::from_list(vec![
+            MoltValue::from_other(Nosy(1)),
+            MoltValue::from_int(2),
+        ]));
+        assert_eq!(myval.as_str(), "1 2");
+
+        // The optional methods, and Debug.  Each poke shimmers the target, so each
+        // needs a fresh one.
+        let nosy5 = || Nosy::target(MoltValue::from_other(Nosy(5)));
+        let myval = nosy5();
+        assert_eq!(myval.compare_other(&myval), Some(Ordering::Equal));
+        assert_eq!(nosy5().hash_other(), Some(5));
+        assert_eq!(MoltValue::clone(&nosy5()).as_other::<Nosy>().unwrap().0, 5);
+        assert!(format!("{:?}", nosy5()).contains("Nosy(5)"));
+    }
+
+    // Sample external type whose methods all access a particular MoltValue, normally
+    // the one that contains them.
+
+    thread_local! {
+        static NOSY_TARGET: RefCell<Option<Rc<MoltValue>>> = const { RefCell::new(None) };
+        static NOSY_POKING: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+    }
+
+    pub struct Nosy(i64);
+
+    impl Nosy {
+        // Makes the value the target, and returns it.
+        fn target(value: MoltValue) -> Rc<MoltValue> {
+            let value = Rc::new(value);
+            NOSY_TARGET.with(|target| *target.borrow_mut() = Some(value.clone()));
+            value
+        }
+
+        // Accesses the target, shimmering it.  Pokes made while poking do nothing,
+        // lest they recurse forever.
+        fn poke() {
+            if NOSY_POKING.with(|poking| poking.replace(true)) {
+                return;
+            }
+
+            if let Some(target) = NOSY_TARGET.with(|target| target.borrow().clone()) {
+                let _ = target.other_type_name();
+                let _ = target.hash_other();
+                let _ = target.as_list();
+                let _ = target.as_str();
+            }
+
+            NOSY_POKING.with(|poking| poking.set(false));
+        }
+    }
+
+    impl Debug for Nosy {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            Nosy::poke();
+            write!(f, "Nosy({})", self.0)
+        }
+    }
+
+    impl MoltType for Nosy {
+        type Err = String;
+
+        fn type_name() -> &'static str {
+            "nosy"
+        }
+
+        fn to_molt_string(&self) -> String {
+            Nosy::poke();
+            self.0.to_string()
+        }
+
+        fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+            Nosy::poke();
+            str.parse::<i64>()
+                .map(Nosy)
+                .map_err(|_| "Not a nosy value".to_string())
+        }
+
+        fn compare(&self, other: &Self) -> Option<Ordering> {
+            Nosy::poke();
+            Some(self.0.cmp(&other.0))
+        }
+
+        fn hash_value(&self) -> Option<u64> {
+            Nosy::poke();
+            Some(self.0 as u64)
+        }
+
+        fn duplicate(&self) -> Option<Self> {
+            Nosy::poke();
+            Some(Nosy(self.0))
+        }
+    }
+
+    // Sample external type that counts the number of times it is parsed and
+    // duplicated, and that defines all of the optional MoltType methods.
+
+    thread_local! {
+        static COUNTED_PARSES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        static COUNTED_DUPLICATES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    #[derive(Debug)]
+    pub struct Counted(i64);
+
+    impl Counted {
+        fn parses() -> usize {
+            COUNTED_PARSES.with(|c| c.get())
+        }
+
+        fn duplicates() -> usize {
+            COUNTED_DUPLICATES.with(|c| c.get())
+        }
+    }
+
+    impl MoltType for Counted {
+        type Err = String;
+
+        fn type_name() -> &'static str {
+            "counted"
+        }
+
+        fn to_molt_string(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+            COUNTED_PARSES.with(|c| c.set(c.get() + 1));
+            str.parse::<i64>()
+                .map(Counted)
+                .map_err(|_| "Not a counted value".to_string())
+        }
+
+        fn compare(&self, other: &Self) -> Option<Ordering> {
+            Some(self.0.cmp(&other.0))
+        }
+
+        fn hash_value(&self) -> Option<u64> {
+            Some(self.0 as u64)
+        }
+
+        fn duplicate(&self) -> Option<Self> {
+            COUNTED_DUPLICATES.with(|c| c.set(c.get() + 1));
+            Some(Counted(self.0))
+        }
     }
 
     // Sample external type, used for testing.
@@ -592,17 +2678,33 @@ mod tests {
     }
 
     impl Flavor {
-        // TODO: The error should be a Molt ResultCode.
         // TODO: This should move to the example.
-        pub fn from_molt(value: &MoltValue) -> Result<Self, String> {
-            if let Some(x) = value.as_copy::<Flavor>() {
+        pub fn from_molt(value: &MoltValue) -> Result<Self, ResultCode> {
+            if let Ok(x) = value.as_copy::<Flavor>() {
                 Ok(x)
             } else {
-                Err("Not a flavor string".to_string())
+                molt_err!("Not a flavor string")
             }
         }
     }
 
+    impl MoltType for Flavor {
+        type Err = String;
+
+        fn type_name() -> &'static str {
+            "flavor"
+        }
+
+        fn to_molt_string(&self) -> String {
+            self.to_string()
+        }
+
+        fn from_molt_str(str: &str) -> Result<Self, Self::Err> {
+            str.parse()
+                .map_err(|_| format!("expected flavor but got \"{}\"", str))
+        }
+    }
+
     impl FromStr for Flavor {
         type Err = String;
 
@@ -628,5 +2730,4 @@ mod tests {
             }
         }
     }
-
 }